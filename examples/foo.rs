@@ -8,7 +8,7 @@ use std::{
 };
 
 use desen::{
-    AppData, AppState, BlendMode, Color, PathBuilder, Stage, TextureInfo, run_app_windowed,
+    AppData, AppState, BlendMode, ClipOp, Color, PathBuilder, Stage, TextureInfo, run_app_windowed,
 };
 
 use dioxus_devtools::subsecond;
@@ -53,7 +53,7 @@ impl AppState for State {
         let mut p = PathBuilder::new();
         p.add_rectangle([0.0, 0.0], [100.0, 50.0]);
         let p = p.build();
-        s.add_clip(&p);
+        s.add_clip(&p, ClipOp::Intersect);
 
         s.fill_color = Color::rgb8(255, 0, 0);
         let mpos = s.mouse_world_pos();
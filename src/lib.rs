@@ -6,10 +6,14 @@ mod state;
 mod util;
 
 pub use stage::{
-    BlendMode, ClipID, Stage,
+    BlendMode, ClipID, ClipOp, Stage,
+    clip::MaskTextureConflict,
     color::Color,
-    path::{Path, PathBuilder},
-    sense::Interactions,
+    input::{InputEvent, RecordedFrame, Recording},
+    keyboard::TextInput,
+    path::{FillRule, Path, PathBuilder},
+    sense::{Cursor, Gestures, Interactions, Scroll, SenseID, SenseOptions},
+    touch::Touch,
 };
 pub use state::{AppData, AppState, texture::TextureInfo, windowed::run_app_windowed};
 pub use winit;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use wgpu::util::DeviceExt;
 
 use crate::{
@@ -6,13 +8,16 @@ use crate::{
         SAMPLE_COUNT,
         shaders::wgsl_main,
         text::{atlas::create_atlases_bind_group, glyph::ContentType},
-        texture::Texture,
+        texture::{Texture, TextureReadback},
     },
+    stage::RenderPass,
     state::texture::TextureMap,
 };
 
 use super::text::atlas::GlyphAtlas;
 
+const PICK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
 pub struct GPUData {
     pub surface: wgpu::Surface<'static>,
     pub device: wgpu::Device,
@@ -23,6 +28,8 @@ pub struct GPUData {
 
     pub normal_pipeline: wgpu::RenderPipeline,
     pub additive_pipeline: wgpu::RenderPipeline,
+    // writes the pick senses instead of colors
+    pub pick_pipeline: wgpu::RenderPipeline,
 
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
@@ -31,6 +38,8 @@ pub struct GPUData {
     pub bind_group_0: wgsl_main::globals::BindGroup0,
 
     pub dummy_texture: wgsl_main::globals::BindGroup1,
+    // bound as the mask texture when no mask needs one
+    pub dummy_mask_texture: Texture,
 
     pub font_system: cosmic_text::FontSystem,
     pub swash_cache: cosmic_text::SwashCache,
@@ -38,6 +47,19 @@ pub struct GPUData {
     pub mask_atlas: GlyphAtlas,
     pub color_atlas: GlyphAtlas,
     pub text_atlas_bind_group: wgsl_main::globals::BindGroup2,
+
+    /// the pick sense under the mouse, from a frame or two ago since it's read back asynchronously
+    pub picked_sense: Option<u64>,
+    // only one at a time, frames in between don't render the pick buffer
+    pick_readback: Option<PickReadback>,
+    // made the first time something can be picked, and again after a resize
+    pick_texture: Option<wgpu::Texture>,
+}
+
+struct PickReadback {
+    pixel: TextureReadback,
+    // the pick senses of the frame it was rendered in, the pixel is an index into them plus 1
+    senses: Vec<u64>,
 }
 
 impl GPUData {
@@ -205,6 +227,36 @@ impl GPUData {
             })
         };
 
+        let pick_pipeline = {
+            let module = wgsl_main::create_shader_module(&device);
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("pick_render_pipeline"),
+                layout: Some(&wgsl_main::create_pipeline_layout(&device)),
+                vertex: crate::render::shaders::make_vertex_state(
+                    &module,
+                    &wgsl_main::entries::vertex_entry_vs_main(
+                        wgpu::VertexStepMode::Vertex,
+                        wgpu::VertexStepMode::Instance,
+                    ),
+                ),
+                fragment: Some(crate::render::shaders::make_fragment_state(
+                    &module,
+                    &wgsl_main::entries::fragment_entry_fs_pick(&[Some(wgpu::ColorTargetState {
+                        format: PICK_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })]),
+                )),
+                primitive: primitive_state,
+                depth_stencil: None,
+                // ids can't be blended between samples
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("vertex_buffer"),
             contents: bytemuck::cast_slice(&[
@@ -242,6 +294,17 @@ impl GPUData {
         let color_atlas = GlyphAtlas::new(&device, ContentType::Color);
         let text_atlas_bind_group = create_atlases_bind_group(&device, &mask_atlas, &color_atlas);
 
+        let dummy_mask_texture = Texture::blank(
+            &device,
+            surface_format,
+            2,
+            2,
+            wgpu::FilterMode::Linear,
+            wgpu::TextureUsages::TEXTURE_BINDING,
+            1,
+            1,
+        );
+
         Self {
             dummy_texture: {
                 let tex = Texture::blank(
@@ -264,6 +327,7 @@ impl GPUData {
                     ),
                 )
             },
+            dummy_mask_texture,
             surface,
             device,
             queue,
@@ -273,6 +337,7 @@ impl GPUData {
             bind_group_0,
             normal_pipeline,
             additive_pipeline,
+            pick_pipeline,
             vertex_buffer,
             index_buffer,
             mask_atlas,
@@ -280,6 +345,9 @@ impl GPUData {
             text_atlas_bind_group,
             font_system: cosmic_text::FontSystem::new(),
             swash_cache: cosmic_text::SwashCache::new(),
+            picked_sense: None,
+            pick_readback: None,
+            pick_texture: None,
         }
     }
 
@@ -290,6 +358,7 @@ impl GPUData {
             self.surface_config.width = width;
             self.surface_config.height = height;
             self.surface.configure(&self.device, &self.surface_config);
+            self.pick_texture = None;
 
             self.queue.write_buffer(
                 &self.globals_buffer,
@@ -320,6 +389,8 @@ impl GPUData {
     }
 
     pub fn render(&mut self, stage: &Stage, loaded_textures: &TextureMap) {
+        self.receive_pick();
+
         let output = self.surface.get_current_texture().unwrap();
         let output_view = output
             .texture
@@ -346,26 +417,137 @@ impl GPUData {
             self.device
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Clip Polygon Points Buffer"),
-                    contents: bytemuck::cast_slice(&stage.clip_polygon_points),
+                    contents: bytemuck::cast_slice(&stage.clips.points),
                     usage: wgpu::BufferUsages::STORAGE,
                 });
         let clip_polygons_buffer =
             self.device
                 .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                     label: Some("Clip Polygons Buffer"),
-                    contents: bytemuck::cast_slice(&stage.clip_polygons),
+                    contents: bytemuck::cast_slice(&stage.clips.polygons),
                     usage: wgpu::BufferUsages::STORAGE,
                 });
-        let bind_group_3 = wgsl_main::globals::BindGroup3::from_bindings(
-            &self.device,
-            wgsl_main::globals::BindGroup3Entries::new(
-                wgsl_main::globals::BindGroup3EntriesEntriesParams {
-                    CLIP_POLYGON_POINTS: clip_polygon_points_buffer.as_entire_buffer_binding(),
-                    CLIP_POLYGONS: clip_polygons_buffer.as_entire_buffer_binding(),
+        let clip_masks_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Clip Masks Buffer"),
+                contents: bytemuck::cast_slice(&stage.clips.masks),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+        let make_bind_group_3 = |mask_texture: &Texture| {
+            wgsl_main::globals::BindGroup3::from_bindings(
+                &self.device,
+                wgsl_main::globals::BindGroup3Entries::new(
+                    wgsl_main::globals::BindGroup3EntriesEntriesParams {
+                        CLIP_POLYGON_POINTS: clip_polygon_points_buffer.as_entire_buffer_binding(),
+                        CLIP_POLYGONS: clip_polygons_buffer.as_entire_buffer_binding(),
+                        CLIP_MASKS: clip_masks_buffer.as_entire_buffer_binding(),
+                        MASK_T: &mask_texture.view,
+                        MASK_S: &mask_texture.sampler,
+                    },
+                ),
+            )
+        };
+        // the mask texture lives in the same group as the clips, so there's one per texture
+        let bind_group_3 = make_bind_group_3(&self.dummy_mask_texture);
+        let mask_bind_groups = stage
+            .render_passes
+            .iter()
+            .flat_map(|pass| &pass.draw_calls)
+            .filter_map(|call| call.set_mask)
+            .map(|tex| (tex, make_bind_group_3(&loaded_textures[tex].texture)))
+            .collect::<HashMap<_, _>>();
+
+        // the pixel under the mouse, only rendered if something can be picked
+        let [width, height] = [self.surface_config.width, self.surface_config.height];
+        let pick_pixel = [
+            stage.mouse_pos.x + width as f32 / 2.0,
+            -stage.mouse_pos.y + height as f32 / 2.0,
+        ];
+        let pick_pixel = (pick_pixel[0] >= 0.0
+            && pick_pixel[1] >= 0.0
+            && pick_pixel[0] < width as f32
+            && pick_pixel[1] < height as f32)
+            .then(|| pick_pixel.map(|v| v as u32));
+        if stage.pick_senses.is_empty() || pick_pixel.is_none() {
+            self.picked_sense = None;
+        }
+        let pick_pixel =
+            pick_pixel.filter(|_| !stage.pick_senses.is_empty() && self.pick_readback.is_none());
+        if pick_pixel.is_some() && self.pick_texture.is_none() {
+            self.pick_texture = Some(self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("pick_texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
                 },
-            ),
-        );
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: PICK_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            }));
+        }
+        let pick_target = pick_pixel
+            .zip(self.pick_texture.as_ref())
+            .map(|(pixel, texture)| {
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (texture, view, pixel)
+            });
 
+        let draw_calls = |render_pass: &mut wgpu::RenderPass<'_>,
+                          pass: &RenderPass,
+                          end_instance: u32,
+                          pick: bool| {
+            render_pass.set_pipeline(if pick {
+                &self.pick_pipeline
+            } else {
+                &self.normal_pipeline
+            });
+            render_pass.set_bind_group(0, self.bind_group_0.get_bind_group(), &[]);
+            render_pass.set_bind_group(1, self.dummy_texture.get_bind_group(), &[]);
+            render_pass.set_bind_group(2, self.text_atlas_bind_group.get_bind_group(), &[]);
+            render_pass.set_bind_group(3, bind_group_3.get_bind_group(), &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+
+            for (idx, call) in pass.draw_calls.iter().enumerate() {
+                // each call draws from its own start, not from the start of the pass
+                let call_start_instance = call.start_instance;
+                let call_end_instance = pass
+                    .draw_calls
+                    .get(idx + 1)
+                    .map(|c| c.start_instance)
+                    .unwrap_or(end_instance);
+
+                // picking doesn't blend
+                if let Some(mode) = call.set_blend_mode
+                    && !pick
+                {
+                    render_pass.set_pipeline(match mode {
+                        BlendMode::Normal => &self.normal_pipeline,
+                        BlendMode::Additive => &self.additive_pipeline,
+                    });
+                }
+                if let Some(tex) = call.set_texture {
+                    render_pass.set_bind_group(
+                        1,
+                        loaded_textures[tex].bind_group.get_bind_group(),
+                        &[],
+                    );
+                }
+                if let Some(tex) = call.set_mask {
+                    render_pass.set_bind_group(3, mask_bind_groups[&tex].get_bind_group(), &[]);
+                }
+
+                render_pass.draw_indexed(0..3, 0, call_start_instance..call_end_instance);
+            }
+        };
+
+        let mut pick_pass_ran = false;
         if !stage.instances.is_empty() {
             let num_instances = stage.instances.len() as u32;
             for (idx, pass) in stage.render_passes.iter().enumerate() {
@@ -396,46 +578,68 @@ impl GPUData {
                         timestamp_writes: None,
                     };
                     let mut render_pass = encoder.begin_render_pass(&pass_desc);
-
-                    render_pass.set_pipeline(&self.normal_pipeline);
-                    render_pass.set_bind_group(0, self.bind_group_0.get_bind_group(), &[]);
-                    render_pass.set_bind_group(1, self.dummy_texture.get_bind_group(), &[]);
-                    render_pass.set_bind_group(2, self.text_atlas_bind_group.get_bind_group(), &[]);
-                    render_pass.set_bind_group(3, bind_group_3.get_bind_group(), &[]);
-                    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-                    render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
-                    render_pass
-                        .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-
-                    for (idx, call) in pass.draw_calls.iter().enumerate() {
-                        let call_start_instance = pass.start_instance;
-                        let call_end_instance = pass
-                            .draw_calls
-                            .get(idx + 1)
-                            .map(|c| c.start_instance)
-                            .unwrap_or(render_pass_end_instance);
-
-                        if let Some(mode) = call.set_blend_mode {
-                            render_pass.set_pipeline(match mode {
-                                BlendMode::Normal => &self.normal_pipeline,
-                                BlendMode::Additive => &self.additive_pipeline,
-                            });
-                        }
-                        if let Some(tex) = call.set_texture {
-                            render_pass.set_bind_group(
-                                1,
-                                loaded_textures[tex].bind_group.get_bind_group(),
-                                &[],
-                            );
-                        }
-
-                        render_pass.draw_indexed(0..3, 0, call_start_instance..call_end_instance);
-                    }
+                    draw_calls(&mut render_pass, pass, render_pass_end_instance, false);
+                }
+                // each pass clears, so only the last one decides what is picked, same as the colors
+                if let Some((_, view, [x, y])) = &pick_target {
+                    pick_pass_ran = true;
+                    let pass_desc = wgpu::RenderPassDescriptor {
+                        label: Some("Pick Pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                        occlusion_query_set: None,
+                        timestamp_writes: None,
+                    };
+                    let mut render_pass = encoder.begin_render_pass(&pass_desc);
+                    // only the pixel under the mouse is needed
+                    render_pass.set_scissor_rect(*x, *y, 1, 1);
+                    draw_calls(&mut render_pass, pass, render_pass_end_instance, true);
                 }
             }
         }
 
         self.queue.submit([encoder.finish()]);
         output.present();
+
+        if let Some((texture, _, pixel)) = pick_target {
+            // the texture is kept between frames, so without a pick pass it still has an old one in it
+            if pick_pass_ran {
+                self.pick_readback = Some(PickReadback {
+                    pixel: TextureReadback::start(
+                        &self.device,
+                        &self.queue,
+                        texture,
+                        pixel,
+                        [1, 1],
+                    ),
+                    senses: stage.pick_senses.clone(),
+                });
+            } else {
+                self.picked_sense = None;
+            }
+        }
+    }
+
+    /// takes the result of the last pick readback if it's there, without waiting for it
+    fn receive_pick(&mut self) {
+        let Some(readback) = &self.pick_readback else {
+            return;
+        };
+        let Some(pixel) = readback.pixel.try_take(&self.device) else {
+            return;
+        };
+
+        let pick = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+        self.picked_sense = pick
+            .checked_sub(1)
+            .and_then(|idx| readback.senses.get(idx as usize).copied());
+        self.pick_readback = None;
     }
 }
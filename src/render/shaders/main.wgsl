@@ -25,6 +25,8 @@ struct InstanceInput {
     // 0: no, 1: mask, 2: color
     @location(13) is_text: u32,
     @location(14) clip_poly: u32,
+    // the low 24 bits are the pick sense + 1 (0 for none), the high 8 the alpha it needs to count
+    @location(15) pick: u32,
 };
 
 struct VertexOutput {
@@ -33,6 +35,9 @@ struct VertexOutput {
     @location(1) uv: vec2f,
     @location(2) is_text: u32,
     @location(3) clip_poly: u32,
+    // position before going to the screen, so clips don't depend on the render target
+    @location(4) world_pos: vec2f,
+    @location(5) pick: u32,
 };
 
 @vertex
@@ -73,16 +78,39 @@ fn vs_main(
     }
     out.is_text = instance.is_text;
     out.clip_poly = instance.clip_poly;
+    out.world_pos = pos;
+    out.pick = instance.pick;
 
     return out;
 }
 
 
 struct ClipPolygon {
+    // goes from world space to the space the clip was added in
+    inv_t_x: vec2f,
+    inv_t_y: vec2f,
+    inv_offset: vec2f,
+
     start_point: u32,
     end_point: u32,
     // 0 is no parent
     parent: u32,
+    // 0: intersect, 1: subtract, 2: union, 3: invert
+    op: u32,
+    // 0: start_point and end_point index CLIP_POLYGON_POINTS
+    // 1: start_point and end_point index CLIP_MASKS
+    kind: u32,
+}
+struct ClipMask {
+    rect_min: vec2f,
+    rect_max: vec2f,
+    uv_min: vec2f,
+    uv_max: vec2f,
+
+    // 0: MASK_T, 1: TEXT_MASK_T, 2: TEXT_COLOR_T
+    source: u32,
+    // which component of the texture is used as the mask
+    channel: u32,
 }
 
 @group(0) @binding(0) var<uniform> GLOBALS: Globals;
@@ -97,6 +125,9 @@ struct ClipPolygon {
 
 @group(3) @binding(0) var<storage> CLIP_POLYGON_POINTS: array<vec2f>;
 @group(3) @binding(1) var<storage> CLIP_POLYGONS: array<ClipPolygon>;
+@group(3) @binding(2) var<storage> CLIP_MASKS: array<ClipMask>;
+@group(3) @binding(3) var MASK_T: texture_2d<f32>;
+@group(3) @binding(4) var MASK_S: sampler;
 
 
 fn fs_color(in: VertexOutput) -> vec4f {
@@ -146,10 +177,36 @@ fn point_in_poly(pos: vec2f, poly: ClipPolygon) -> bool {
     return c;
 }
 
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4f {
-    let color = fs_color(in);
+// the glyphs of a text mask are all part of the same clip, so this takes the max of all of them
+fn mask_weight(local: vec2f, poly: ClipPolygon) -> f32 {
+    var weight = 0.0;
+    for(var i = poly.start_point; i < poly.end_point; i++) {
+        let mask = CLIP_MASKS[i];
+
+        if any(local < mask.rect_min) || any(local > mask.rect_max) {
+            continue;
+        }
+        let uv = mix(mask.uv_min, mask.uv_max, (local - mask.rect_min) / (mask.rect_max - mask.rect_min));
+
+        var sample: vec4f;
+        switch mask.source {
+            case 1u: {
+                sample = textureSampleLevel(TEXT_MASK_T, TEXT_MASK_S, uv / vec2f(textureDimensions(TEXT_MASK_T)), 0.0);
+            }
+            case 2u: {
+                sample = textureSampleLevel(TEXT_COLOR_T, TEXT_COLOR_S, uv / vec2f(textureDimensions(TEXT_COLOR_T)), 0.0);
+            }
+            default: {
+                sample = textureSampleLevel(MASK_T, MASK_S, uv, 0.0);
+            }
+        }
+        weight = max(weight, sample[mask.channel]);
+    }
+    return weight;
+}
 
+// how visible the fragment is through its clips
+fn clip_weight(in: VertexOutput) -> f32 {
     var antialias = array(
         vec2(-3.0 / 8.0, 1.0 / 8.0),
         vec2(1.0 / 8.0, 3.0 / 8.0),
@@ -158,27 +215,76 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4f {
         vec2(0.0, 0.0),
     );
 
-    let world_pos = (in.pos.xy - GLOBALS.screen_size / 2.0) * vec2(1.0, -1.0);
-
-    var final_weight = 1.0;
+    // each clip maps the weight of its parent `p` to `a * p + b`,
+    // so walking up the chain we just compose those
+    var final_a = 1.0;
+    var final_b = 0.0;
 
     var clip_poly = in.clip_poly;
     while clip_poly != 0 {
         let poly = CLIP_POLYGONS[clip_poly];
 
+        let inv_t = mat2x2f(poly.inv_t_x, poly.inv_t_y);
+
         var weight = 0.0;
 
-        for (var i = 0; i < 5; i++) {
-            let pos = world_pos + antialias[i];
-            if point_in_poly(pos, poly) {
-                weight += 1.0;
+        if poly.op == 3 {
+            // inverting doesn't look at the clip itself
+        } else if poly.kind == 1 {
+            // textures are already smooth, no need to antialias
+            weight = mask_weight(inv_t * in.world_pos + poly.inv_offset, poly);
+        } else {
+            for (var i = 0; i < 5; i++) {
+                // the samples are spread in world space, so they stay about a pixel apart
+                let pos = inv_t * (in.world_pos + antialias[i]) + poly.inv_offset;
+                if point_in_poly(pos, poly) {
+                    weight += 1.0;
+                }
+            }
+            weight /= 5;
+        }
+
+        var a = 0.0;
+        var b = 0.0;
+        switch poly.op {
+            case 0u: {
+                a = weight;
+            }
+            case 1u: {
+                a = 1.0 - weight;
+            }
+            case 2u: {
+                a = 1.0 - weight;
+                b = weight;
+            }
+            default: {
+                a = -1.0;
+                b = 1.0;
             }
         }
 
-        final_weight *= weight / 5;
+        final_b += final_a * b;
+        final_a *= a;
 
         clip_poly = poly.parent;
     }
+    // no clip at the root means everything is visible
+    return final_a + final_b;
+}
 
-    return vec4(color.rgb, color.a * final_weight);
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let color = fs_color(in);
+    return vec4(color.rgb, color.a * clip_weight(in));
+}
+
+// writes which pick sense is drawn where, things drawn without one cover them with 0
+@fragment
+fn fs_pick(in: VertexOutput) -> @location(0) u32 {
+    let alpha = fs_color(in).a * clip_weight(in);
+    let threshold = f32(in.pick >> 24u) / 255.0;
+    if alpha <= 0.0 || alpha < threshold {
+        discard;
+    }
+    return in.pick & 0xffffffu;
 }
\ No newline at end of file
@@ -58,6 +58,7 @@ pub fn prepare_glyph(
     offset_x: f32,
     offset_y: f32,
     clip_poly: u32,
+    pick: u32,
 ) -> Option<[wgsl_main::structs::InstanceInput; 2]> {
     let data = if let Some(d) = gpu_data.mask_atlas.glyph_cache.get(&physical.cache_key) {
         gpu_data.mask_atlas.glyphs_in_use.insert(physical.cache_key);
@@ -203,6 +204,7 @@ pub fn prepare_glyph(
                 2
             },
             clip_poly,
+            pick,
         ),
         wgsl_main::structs::InstanceInput::new(
             points[2],
@@ -223,6 +225,7 @@ pub fn prepare_glyph(
                 2
             },
             clip_poly,
+            pick,
         ),
     ])
 }
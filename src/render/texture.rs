@@ -1,3 +1,8 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
 use image::GenericImageView;
 
 pub struct Texture {
@@ -169,4 +174,108 @@ impl Texture {
     //         sampler,
     //     }
     // }
+
+    /// copies the texture to a buffer that can be read once the gpu is done, see `TextureReadback::try_take`.
+    /// the texture needs `COPY_SRC` and a 4 byte format
+    pub fn start_read_rgba(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+    ) -> TextureReadback {
+        TextureReadback::start(device, queue, &self.texture, [0, 0], [width, height])
+    }
+}
+
+/// a texture on its way back from the gpu
+pub struct TextureReadback {
+    buffer: wgpu::Buffer,
+    mapped: Arc<AtomicBool>,
+    row_bytes: u32,
+    padded_row_bytes: u32,
+}
+
+impl TextureReadback {
+    /// copies the part of `texture` from `origin` on, after what was already submitted.
+    /// the texture needs `COPY_SRC` and a 4 byte format
+    pub(crate) fn start(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        origin: [u32; 2],
+        [width, height]: [u32; 2],
+    ) -> Self {
+        // rows in the buffer have to be aligned
+        let row_bytes = 4 * width;
+        let padded_row_bytes = row_bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Texture Read Buffer"),
+            size: (padded_row_bytes * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Texture Read Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin[0],
+                    y: origin[1],
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let mapped = Arc::new(AtomicBool::new(false));
+        let done = mapped.clone();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                done.store(result.is_ok(), Ordering::Release);
+            });
+        Self {
+            buffer,
+            mapped,
+            row_bytes,
+            padded_row_bytes,
+        }
+    }
+
+    /// the pixels if the gpu is done, without waiting for it
+    pub fn try_take(&self, device: &wgpu::Device) -> Option<Vec<u8>> {
+        // a failed poll just means the result comes later
+        let _ = device.poll(wgpu::PollType::Poll);
+        if !self.mapped.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let data = self.buffer.slice(..).get_mapped_range();
+        Some(
+            data.chunks(self.padded_row_bytes as usize)
+                .flat_map(|row| &row[..self.row_bytes as usize])
+                .copied()
+                .collect(),
+        )
+    }
 }
@@ -0,0 +1,174 @@
+use std::{error::Error, fmt};
+
+use glam::{Affine2, Vec2};
+
+use crate::{
+    render::shaders::wgsl_main::structs::{ClipMask, ClipPolygon, InstanceInput},
+    state::texture::TextureKey,
+};
+
+// 0: intersect, 1: subtract, 2: union, 3: invert
+pub const CLIP_OP_INVERT: u32 = 3;
+
+pub const CLIP_KIND_POLYGON: u32 = 0;
+pub const CLIP_KIND_MASK: u32 = 1;
+
+/// in pixels, anything smaller would flatten curves into endless points
+pub const MIN_CLIP_TOLERANCE: f32 = 0.01;
+
+/// a mask was added on top of a clip chain that already samples another texture,
+/// only one can be bound at a time so the clip wasn't added
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaskTextureConflict;
+impl fmt::Display for MaskTextureConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("masks in the same clip chain can't use different textures")
+    }
+}
+impl Error for MaskTextureConflict {}
+
+/// everything the gpu needs to know about the clips of a frame
+#[derive(Debug, Default)]
+pub struct Clips {
+    pub(crate) points: Vec<[f32; 2]>,
+    pub(crate) polygons: Vec<ClipPolygon>,
+    pub(crate) masks: Vec<ClipMask>,
+    /// the texture that the masks in the chain of each clip sample from, if any
+    pub(crate) mask_textures: Vec<Option<TextureKey>>,
+}
+
+impl Clips {
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.polygons.clear();
+        self.masks.clear();
+        self.mask_textures.clear();
+        // there need to be some items in
+        self.points.push([0.0; 2]);
+        self.polygons
+            .push(make_polygon(Affine2::IDENTITY, 0, 0, 0, 0, 0));
+        self.masks
+            .push(ClipMask::new([0.0; 2], [0.0; 2], [0.0; 2], [0.0; 2], 0, 0));
+        self.mask_textures.push(None);
+    }
+
+    /// same as the clip chain evaluation in `fs_main`, minus the antialiasing.
+    /// masks can't be sampled on the cpu so they count as their whole rect
+    pub fn contains(&self, mut clip: u32, pos: Vec2) -> bool {
+        let mut final_a = 1.0;
+        let mut final_b = 0.0;
+
+        while clip != 0 {
+            let poly = self.polygons[clip as usize];
+            let local = Affine2::from_cols(
+                poly.inv_t_x.into(),
+                poly.inv_t_y.into(),
+                poly.inv_offset.into(),
+            )
+            .transform_point2(pos);
+
+            let inside = if poly.op == CLIP_OP_INVERT {
+                false
+            } else if poly.kind == CLIP_KIND_MASK {
+                self.point_in_masks(poly, local)
+            } else {
+                self.point_in_poly(poly, local)
+            };
+            let weight = if inside { 1.0 } else { 0.0 };
+
+            let (a, b) = match poly.op {
+                0 => (weight, 0.0),
+                1 => (1.0 - weight, 0.0),
+                2 => (1.0 - weight, weight),
+                _ => (-1.0, 1.0),
+            };
+
+            final_b += final_a * b;
+            final_a *= a;
+
+            clip = poly.parent;
+        }
+
+        final_a + final_b > 0.5
+    }
+
+    /// same as `point_in_poly` in the shader
+    fn point_in_poly(&self, poly: ClipPolygon, pos: Vec2) -> bool {
+        let points = &self.points[poly.start_point as usize..poly.end_point as usize];
+
+        let mut c = false;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+
+            if pos.x == a[0] && pos.y == a[1] {
+                return true;
+            }
+            if (a[1] > pos.y) != (b[1] > pos.y) {
+                let slope = (pos.x - a[0]) * (b[1] - a[1]) - (b[0] - a[0]) * (pos.y - a[1]);
+                if slope == 0.0 {
+                    return true;
+                }
+                if (slope < 0.0) != (b[1] < a[1]) {
+                    c = !c;
+                }
+            }
+        }
+        c
+    }
+
+    fn point_in_masks(&self, poly: ClipPolygon, pos: Vec2) -> bool {
+        self.masks[poly.start_point as usize..poly.end_point as usize]
+            .iter()
+            .any(|mask| {
+                pos.cmpge(mask.rect_min.into()).all() && pos.cmple(mask.rect_max.into()).all()
+            })
+    }
+}
+
+/// the clip geometry stays in the space it was added in, `transform` is what goes from there to world space.
+/// a degenerate transform squashes the geometry flat, so the clip is left empty
+pub fn make_polygon(
+    transform: Affine2,
+    start_point: u32,
+    mut end_point: u32,
+    parent: u32,
+    op: u32,
+    kind: u32,
+) -> ClipPolygon {
+    let inv = if is_degenerate(transform) {
+        end_point = start_point;
+        Affine2::ZERO
+    } else {
+        transform.inverse()
+    };
+    ClipPolygon::new(
+        inv.matrix2.x_axis.to_array(),
+        inv.matrix2.y_axis.to_array(),
+        inv.translation.to_array(),
+        start_point,
+        end_point,
+        parent,
+        op,
+        kind,
+    )
+}
+
+/// the transform has no inverse, everything it maps ends up on a line or a point
+pub fn is_degenerate(transform: Affine2) -> bool {
+    let det = transform.matrix2.determinant();
+    det == 0.0 || !det.is_finite() || !transform.translation.is_finite()
+}
+
+/// turns the two triangles made by `prepare_glyph` into a mask that samples the glyph atlas.
+/// the glyphs are in the space of the text, which is the space of the clip
+pub fn mask_from_glyph([a, _]: [InstanceInput; 2]) -> ClipMask {
+    // the first triangle goes (0, 0), (w, 0), (w, -h) so it has all we need
+    ClipMask::new(
+        [a.pos0[0], a.pos2[1]],
+        [a.pos2[0], a.pos0[1]],
+        [a.uv0[0], a.uv2[1]],
+        [a.uv2[0], a.uv0[1]],
+        a.is_text,
+        if a.is_text == 1 { 0 } else { 3 },
+    )
+}
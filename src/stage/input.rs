@@ -0,0 +1,177 @@
+use std::{fs, io};
+
+use serde::{Deserialize, Serialize};
+use winit::{
+    event::{Ime, MouseButton, TouchPhase},
+    keyboard::{Key, ModifiersState, PhysicalKey},
+};
+
+/// something from outside that changes the stage, what the window sends and what recordings are made of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    /// in world space, so the middle of the window is (0, 0) and y goes up
+    MouseMoved([f32; 2]),
+    MousePressed(MouseButton),
+    MouseReleased(MouseButton),
+    /// the position is in world space like `MouseMoved`
+    Touch {
+        id: u64,
+        pos: [f32; 2],
+        phase: TouchPhase,
+    },
+    /// in world directions like `Scroll`
+    ScrollLines([f32; 2]),
+    ScrollPixels([f32; 2]),
+    /// multiplies the zoom, above 1 is zooming in
+    Zoom(f32),
+    /// in radians, counterclockwise
+    Rotate(f32),
+    Pan([f32; 2]),
+    Key {
+        physical: PhysicalKey,
+        logical: Key,
+        /// what pressing it types, if anything
+        text: Option<String>,
+        pressed: bool,
+        repeat: bool,
+    },
+    Ime(Ime),
+    Modifiers(ModifiersState),
+    /// the window lost focus, so all keys count as released
+    FocusLost,
+}
+
+/// the input of a number of frames, see `Stage::start_recording` and `Stage::replay`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// seconds since the last frame
+    pub delta: f64,
+    pub window_size: [f32; 2],
+    /// what came in since the last frame, in order
+    pub events: Vec<InputEvent>,
+}
+
+impl Recording {
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        let text = ron::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+    pub fn load(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SenseID, SenseOptions, Stage};
+
+    fn frame(stage: &mut Stage) -> crate::Interactions<bool> {
+        stage.next_frame(0.1);
+        stage.rect_sense().x(-10.0).y(-10.0).w(20.0).h(20.0).test()
+    }
+
+    #[test]
+    fn extra_buttons_are_tracked() {
+        // winit numbers the extra buttons from 10 on x11 and from 0x117 on wayland
+        let button = MouseButton::Other(0x117);
+        let mut stage = Stage::new();
+        stage.input(InputEvent::MouseMoved([0.0, 0.0]));
+        frame(&mut stage);
+        frame(&mut stage);
+
+        stage.input(InputEvent::MousePressed(button));
+        let sense = frame(&mut stage);
+        assert!(stage.mouse_button_down(button));
+        assert!(stage.mouse_button_pressed(button));
+        assert!(sense.button_click_started(button));
+        assert!(!sense.click_started);
+
+        stage.input(InputEvent::MouseReleased(button));
+        let sense = frame(&mut stage);
+        assert!(!stage.mouse_button_down(button));
+        assert!(stage.mouse_button_released(button));
+        assert!(sense.button_click_ended(button));
+        assert!(!sense.button_holding(button));
+    }
+
+    #[test]
+    fn cancelled_touch_is_no_click() {
+        let touch = |phase| InputEvent::Touch {
+            id: 1,
+            pos: [0.0, 0.0],
+            phase,
+        };
+        let mut stage = Stage::new();
+        frame(&mut stage);
+        frame(&mut stage);
+
+        stage.input(touch(TouchPhase::Started));
+        let sense = frame(&mut stage);
+        assert!(sense.holding);
+
+        stage.input(touch(TouchPhase::Cancelled));
+        let sense = frame(&mut stage);
+        assert!(!sense.holding);
+        assert!(!sense.click_ended);
+        assert!(!stage.mouse_down());
+    }
+
+    #[test]
+    fn focus_by_id() {
+        // two focusable senses, whether they're focused and their ids
+        fn frame(stage: &mut Stage) -> [(bool, SenseID); 2] {
+            stage.next_frame(0.1);
+            let options = SenseOptions::builder().focus_order(0).build();
+            [(); 2].map(|_| {
+                let sense = stage.rect_sense().options(options).test();
+                (sense.focused, stage.last_sense_id().unwrap())
+            })
+        }
+        let mut stage = Stage::new();
+        let [_, (_, second)] = frame(&mut stage);
+        stage.focus(second);
+        let [(_, first), (focused, _)] = frame(&mut stage);
+        assert!(focused);
+
+        stage.focus(first);
+        let [(first, _), (second, _)] = frame(&mut stage);
+        assert!(first && !second);
+    }
+
+    #[test]
+    fn replay_drains() {
+        let mut stage = Stage::new();
+        stage.start_recording();
+        stage.input(InputEvent::MouseMoved([10.0, 20.0]));
+        stage.next_frame(0.1);
+        stage.input(InputEvent::MouseMoved([30.0, 40.0]));
+        stage.next_frame(0.2);
+        let recording = stage.stop_recording().unwrap();
+        assert_eq!(recording.frames.len(), 2);
+
+        let mut stage = Stage::new();
+        stage.replay(recording);
+        // live input is ignored while replaying
+        stage.input(InputEvent::MouseMoved([-1.0, -1.0]));
+        stage.next_frame(1.0);
+        assert_eq!(stage.mouse_world_pos(), [10.0, 20.0]);
+        assert_eq!(stage.delta(), 0.1);
+        assert!(stage.is_replaying());
+        stage.next_frame(1.0);
+        assert_eq!(stage.mouse_world_pos(), [30.0, 40.0]);
+        assert_eq!(stage.delta(), 0.2);
+        assert!(!stage.is_replaying());
+
+        stage.input(InputEvent::MouseMoved([5.0, 5.0]));
+        stage.next_frame(1.0);
+        assert_eq!(stage.mouse_world_pos(), [5.0, 5.0]);
+        assert_eq!(stage.delta(), 1.0);
+    }
+}
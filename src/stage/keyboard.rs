@@ -0,0 +1,99 @@
+use std::{collections::HashMap, mem::take};
+
+use winit::{
+    event::Ime,
+    keyboard::{Key, ModifiersState, PhysicalKey},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextInput {
+    /// text that should be inserted at the caret, from typing or from the ime
+    Text(String),
+    /// the text the ime is composing, replaces the previous one.
+    /// an empty string means the composition ended or was cancelled.
+    /// the range is the byte range of the ime's cursor in the text
+    Preedit(String, Option<(usize, usize)>),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KeyChanges {
+    pub(crate) pressed: Vec<(PhysicalKey, Key)>,
+    pub(crate) released: Vec<(PhysicalKey, Key)>,
+    pub(crate) repeated: Vec<(PhysicalKey, Key)>,
+    pub(crate) text: Vec<TextInput>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Keyboard {
+    // the keys that are down and the logical key they were pressed as,
+    // so releasing works even if the modifiers changed in between
+    pub(crate) down: HashMap<PhysicalKey, Key>,
+    pub(crate) modifiers: ModifiersState,
+    pub(crate) preedit: Option<(String, Option<(usize, usize)>)>,
+
+    pub(crate) frame: KeyChanges,
+    // collected from the events until the next frame starts
+    pub(crate) next_frame: KeyChanges,
+}
+
+impl Keyboard {
+    pub(crate) fn start(&mut self) {
+        self.frame = take(&mut self.next_frame);
+    }
+
+    pub(crate) fn key_event(
+        &mut self,
+        physical: PhysicalKey,
+        logical: Key,
+        text: Option<&str>,
+        pressed: bool,
+        repeat: bool,
+    ) {
+        if pressed {
+            // while the ime is composing the keys go to it, the text comes with the commit
+            if let Some(text) = text
+                && self.preedit.is_none()
+                && !text.chars().any(char::is_control)
+            {
+                self.next_frame.text.push(TextInput::Text(text.to_string()));
+            }
+
+            if repeat {
+                self.next_frame.repeated.push((physical, logical));
+            } else {
+                self.down.insert(physical, logical.clone());
+                self.next_frame.pressed.push((physical, logical));
+            }
+        } else {
+            let logical = self.down.remove(&physical).unwrap_or(logical);
+            self.next_frame.released.push((physical, logical));
+        }
+    }
+
+    pub(crate) fn ime_event(&mut self, event: Ime) {
+        match event {
+            Ime::Preedit(text, cursor) => {
+                self.preedit = (!text.is_empty()).then(|| (text.clone(), cursor));
+                self.next_frame.text.push(TextInput::Preedit(text, cursor));
+            }
+            Ime::Commit(text) => {
+                self.preedit = None;
+                self.next_frame.text.push(TextInput::Text(text));
+            }
+            Ime::Disabled => {
+                if self.preedit.take().is_some() {
+                    self.next_frame
+                        .text
+                        .push(TextInput::Preedit(String::new(), None));
+                }
+            }
+            Ime::Enabled => {}
+        }
+    }
+
+    /// the window lost focus so we won't hear about any of these being released
+    pub(crate) fn release_all(&mut self) {
+        self.next_frame.released.extend(self.down.drain());
+        self.modifiers = ModifiersState::empty();
+    }
+}
@@ -1,24 +1,48 @@
+pub mod clip;
 pub mod color;
+pub mod input;
+pub mod keyboard;
+pub mod mouse;
 pub mod path;
 pub mod sense;
+pub mod sense_grid;
+pub mod touch;
 
 use std::{
     any::{Any, TypeId},
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     f32::consts::PI,
     hash::{DefaultHasher, Hash, Hasher},
-    mem::swap,
+    iter::successors,
+    mem::{replace, swap, take},
     rc::Rc,
-    time::Instant,
+    time::Duration,
 };
 
 use cosmic_text::AttrsOwned;
 use itertools::Itertools;
 
 use glam::{Affine2, Mat2, Vec2, Vec4, vec2};
+use touch::{Touch, Touches};
+use winit::{
+    event::{MouseButton, TouchPhase},
+    keyboard::{Key, ModifiersState, NamedKey, PhysicalKey},
+    window::CursorIcon,
+};
 
-use lyon::algorithms::walk;
-use sense::{Interactions, SenseSave, SenseShape, SenseShapeType, test_in_shape};
+use clip::{
+    CLIP_KIND_MASK, CLIP_KIND_POLYGON, CLIP_OP_INVERT, Clips, MIN_CLIP_TOLERANCE,
+    MaskTextureConflict, is_degenerate, make_polygon, mask_from_glyph,
+};
+use input::{InputEvent, RecordedFrame, Recording};
+use keyboard::{KeyChanges, Keyboard, TextInput};
+use mouse::{ButtonMap, MouseButtons};
+use path::FillRule;
+use sense::{
+    Cursor, Gestures, Interactions, Scroll, SenseID, SenseOptions, SenseSave, SenseShape,
+    SenseShapeType, shape_rect, test_in_shape,
+};
+use sense_grid::SenseGrid;
 
 use crate::{
     AppData, Path,
@@ -34,6 +58,26 @@ use crate::{
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 pub struct ClipID(u32);
 
+/// how a newly added clip combines with the clip it is added on top of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipOp {
+    /// only draw inside the path and inside the parent clip
+    Intersect,
+    /// draw inside the parent clip, except inside the path
+    Subtract,
+    /// draw inside the path or inside the parent clip
+    Union,
+}
+impl ClipOp {
+    pub(crate) fn to_u32(self) -> u32 {
+        match self {
+            ClipOp::Intersect => 0,
+            ClipOp::Subtract => 1,
+            ClipOp::Union => 2,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BlendMode {
     Normal,
@@ -45,8 +89,25 @@ pub struct DrawCall {
     pub start_instance: u32,
     pub set_blend_mode: Option<BlendMode>,
     pub set_texture: Option<TextureKey>,
+    pub set_mask: Option<TextureKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FocusRequest {
+    Sense(u64),
+    Next,
+    Previous,
+    Clear,
 }
 
+/// things drawn outside of pick senses still cover them where they are at least half visible
+const NO_PICK: u32 = 128 << 24;
+/// the index under the threshold is one more than the pick sense, 0 is none
+const MAX_PICK_SENSES: usize = (1 << 24) - 1;
+
+/// see `Stage::defer`
+type DeferredDraw = Box<dyn FnOnce(&mut Stage, &mut AppData)>;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct RenderPass {
     pub start_instance: u32,
@@ -56,8 +117,9 @@ pub struct RenderPass {
 pub struct Stage {
     // gpu related -------------------------------
     pub(crate) instances: Vec<wgsl_main::structs::InstanceInput>,
-    pub(crate) clip_polygon_points: Vec<[f32; 2]>,
-    pub(crate) clip_polygons: Vec<wgsl_main::structs::ClipPolygon>,
+    pub(crate) clips: Clips,
+    // the clips of the last frame, needed to test `old_senses`
+    pub(crate) old_clips: Clips,
 
     pub(crate) render_passes: Vec<RenderPass>,
 
@@ -70,6 +132,9 @@ pub struct Stage {
     pub draw_stroke: bool,
 
     pub arc_segments: u16,
+    /// max distance in pixels between a curve and the polygon approximating it when adding clips or path senses.
+    /// anything below 0.01 counts as 0.01
+    pub clip_tolerance: f32,
 
     pub transform: Affine2,
 
@@ -77,19 +142,97 @@ pub struct Stage {
     pub(crate) current_texture: Option<TextureInfo>,
 
     pub(crate) current_clip: u32,
+    pub(crate) current_mask_texture: Option<TextureKey>,
+    // the caret rect in world space, the ime is only allowed when this is set
+    pub(crate) ime_caret: Option<[Vec2; 2]>,
+    // what the instances get as `pick`, see `pick_sense`
+    pub(crate) current_pick: u32,
+    // drawn on top of everything when the frame is done
+    pub(crate) deferred: Vec<DeferredDraw>,
+
+    // settings, these are kept between frames -------------------------------
+    /// how far in pixels the mouse has to move while holding a sense before it counts as dragging
+    pub drag_threshold: f32,
+    /// how long after a click the next one can come to count as a double click.
+    /// measured in frame time, the sum of the deltas, so a replay counts them the same
+    pub double_click_interval: Duration,
+    /// how far in pixels apart clicks can be to count as a double click
+    pub double_click_slop: f32,
+    /// how long a sense has to be held without dragging for `long_pressed`, in frame time like `double_click_interval`
+    pub long_press_duration: Duration,
+    /// how long a sense has to be hovered before `hover_delay_elapsed` is set, used for tooltips
+    pub hover_delay: Duration,
+    /// move the focus with tab and shift tab, turn it off if tab is needed for something else
+    pub tab_navigation: bool,
+    /// the cursor when the hovered sense doesn't have one
+    pub default_cursor: Cursor,
+    /// runs `render` twice a frame, first only to collect the senses, so interactions
+    /// are tested against this frame's senses instead of the last one's.
+    /// in the first pass senses return what lasts of their interactions from the last frame, like hovering,
+    /// without what happens once, like clicks. so code that reacts to those only runs once,
+    /// anything else that changes state (`temp` too) runs twice unless it's skipped with `is_layout_pass`.
+    /// `delta` is 0 in the first pass
+    pub same_frame_interactions: bool,
 
     // outside handled readonly -------------------------------
     pub(crate) mouse_pos: Vec2,
-    pub(crate) mouse_down: Option<u64>,
-    pub(crate) right_mouse_down: Option<u64>,
+    pub(crate) mouse_buttons: MouseButtons,
+    // where and when the left button was last pressed
+    pub(crate) mouse_down_pos: Vec2,
+    pub(crate) mouse_down_time: Duration,
+    // collected from the events until the next frame starts
+    pub(crate) next_scroll: Scroll,
+    pub(crate) next_gestures: Gestures,
+    pub(crate) keyboard: Keyboard,
+    pub(crate) touches: Touches,
     pub(crate) delta: f64,
+    // the sum of the deltas, used instead of the clock so replays behave the same
+    pub(crate) time: Duration,
+    pub(crate) window_size: Vec2,
+    // the pick sense the gpu found under the mouse
+    pub(crate) picked_sense: Option<u64>,
+    // the frames recorded so far and the events since the last one
+    pub(crate) recording: Option<Recording>,
+    pub(crate) recording_events: Vec<InputEvent>,
+    // the frames that are still to be replayed, the window is ignored until they are done
+    pub(crate) replay: VecDeque<RecordedFrame>,
 
     // interaction -------------------------------
     pub(crate) old_senses: Vec<SenseSave>,
     pub(crate) build_senses: Vec<SenseSave>,
+    // flattened outlines of path senses, same as the clip points
+    pub(crate) old_sense_points: Vec<[f32; 2]>,
+    pub(crate) build_sense_points: Vec<[f32; 2]>,
+    // which of `old_senses` are where, for finding the ones under the mouse quickly
+    pub(crate) old_sense_grid: SenseGrid,
     pub(crate) sense_id_ctr: u64,
+    // the id of each scope and the counter for the unkeyed senses in it
+    pub(crate) id_stack: Vec<(u64, u64)>,
+    pub(crate) layout_pass: bool,
+    // what lasts of the interactions of each sense from the last real pass, for the layout pass
+    pub(crate) last_interactions: HashMap<u64, Interactions<bool>>,
+    // the pick senses of this frame, the gpu writes their index + 1
+    pub(crate) pick_senses: Vec<u64>,
+    // the senses that pass their clicks up, and the parent they go to
+    pub(crate) sense_parents: HashMap<u64, u64>,
+    // everything under the mouse from the top down
+    pub(crate) senses_under_mouse: Vec<u64>,
+    // the pass through senses above the hovered one, this and last frame
+    pub(crate) pass_through_hovering: Vec<u64>,
+    pub(crate) old_pass_through_hovering: Vec<u64>,
 
     pub(crate) interactions: Interactions<Option<u64>>,
+    pub(crate) last_mouse_pos: Vec2,
+    // the sense, time and position of the last press, for counting clicks
+    pub(crate) last_press: Option<(u64, Duration, Vec2)>,
+    pub(crate) click_count: u32,
+    pub(crate) long_press_done: bool,
+    // when the hovered sense started being hovered
+    pub(crate) hover_time: Duration,
+    // what is carried by the dragged sense, the id is the one of that sense
+    pub(crate) drag_payload: Option<(u64, Box<dyn Any>)>,
+    // applied when the next frame starts, since focus works on `old_senses`
+    pub(crate) focus_request: Option<FocusRequest>,
 
     // cached/temp -------------------------------
     pub(crate) cached_buffers:
@@ -98,12 +241,20 @@ pub struct Stage {
     pub(crate) temp_states: HashMap<(TypeId, TypeId, u64), (Box<dyn Any>, bool)>,
 }
 
+impl Default for Stage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Stage {
-    pub(crate) fn new() -> Self {
+    /// a stage without a window, drive it with `input` and `next_frame`.
+    /// drawing text needs the gpu, so it can't be done on it
+    pub fn new() -> Self {
         let mut out = Self {
             instances: vec![],
-            clip_polygon_points: vec![],
-            clip_polygons: vec![],
+            clips: Clips::default(),
+            old_clips: Clips::default(),
             render_passes: vec![],
             fill_color: Color::rgba8(0, 0, 0, 0),
             stroke_color: Color::rgba8(0, 0, 0, 0),
@@ -111,43 +262,157 @@ impl Stage {
             draw_fill: false,
             draw_stroke: false,
             arc_segments: 0,
+            clip_tolerance: 0.0,
             transform: Affine2::IDENTITY,
             current_blend_mode: BlendMode::Normal,
             current_texture: None,
             current_clip: 0,
+            current_mask_texture: None,
+            ime_caret: None,
+            current_pick: NO_PICK,
+            deferred: vec![],
+            drag_threshold: 4.0,
+            double_click_interval: Duration::from_millis(500),
+            double_click_slop: 4.0,
+            long_press_duration: Duration::from_millis(500),
+            hover_delay: Duration::from_millis(500),
+            tab_navigation: true,
+            default_cursor: Cursor::Icon(CursorIcon::Default),
+            same_frame_interactions: false,
             old_senses: vec![],
             build_senses: vec![],
+            old_sense_points: vec![],
+            build_sense_points: vec![],
+            old_sense_grid: SenseGrid::default(),
             sense_id_ctr: 0,
+            id_stack: vec![],
+            layout_pass: false,
+            last_interactions: HashMap::new(),
+            pick_senses: vec![],
+            sense_parents: HashMap::new(),
+            senses_under_mouse: vec![],
+            pass_through_hovering: vec![],
+            old_pass_through_hovering: vec![],
             mouse_pos: Vec2::INFINITY,
-            mouse_down: None,
-            right_mouse_down: None,
+            mouse_buttons: MouseButtons::default(),
+            mouse_down_pos: Vec2::ZERO,
+            mouse_down_time: Duration::ZERO,
+            next_scroll: Scroll::default(),
+            next_gestures: Gestures::default(),
+            keyboard: Keyboard::default(),
+            touches: Touches::default(),
             delta: 0.0,
+            time: Duration::ZERO,
+            window_size: Vec2::ZERO,
+            picked_sense: None,
+            #[allow(deprecated)]
             interactions: Interactions {
                 hovering: None,
                 hovering_bypass: None,
                 hover_started: None,
                 hover_ended: None,
+                hover_delay_elapsed: None,
                 holding: None,
                 click_started: None,
                 click_ended: None,
+                long_pressed: None,
                 right_holding: None,
                 right_click_started: None,
                 right_click_ended: None,
+                focused: None,
+                focus_gained: None,
+                focus_lost: None,
+                dragging: None,
+                drag_started: None,
+                drag_ended: None,
+                drop_hovered: None,
+                dropped: None,
+                hover_duration: Duration::ZERO,
+                scroll: Scroll::default(),
+                gestures: Gestures::default(),
+                click_count: 0,
+                touches: 0,
+                drag_delta: [0.0; 2],
+                drag_local_delta: [0.0; 2],
+                drag_offset: [0.0; 2],
+                drag_local_offset: [0.0; 2],
+                mouse_local_pos: [0.0; 2],
+                mouse_normalized_pos: [0.0; 2],
+                buttons_holding: ButtonMap::default(),
+                buttons_click_started: ButtonMap::default(),
+                buttons_click_ended: ButtonMap::default(),
             },
             cached_buffers: HashMap::new(),
+            last_mouse_pos: Vec2::INFINITY,
+            last_press: None,
+            click_count: 0,
+            long_press_done: false,
+            hover_time: Duration::ZERO,
+            recording: None,
+            recording_events: vec![],
+            replay: VecDeque::new(),
+            drag_payload: None,
+            focus_request: None,
             temp_states: HashMap::new(),
         };
         out.start();
         out
     }
     pub(crate) fn start(&mut self) {
+        self.reset();
+        self.layout_pass = false;
+        self.last_interactions.clear();
+
+        if let Some(recording) = &mut self.recording {
+            recording.frames.push(RecordedFrame {
+                delta: self.delta,
+                window_size: self.window_size.to_array(),
+                events: take(&mut self.recording_events),
+            });
+        }
+        self.time += Duration::try_from_secs_f64(self.delta).unwrap_or_default();
+
+        self.keyboard.start();
+        self.mouse_buttons.start();
+        self.touches.start();
+        self.update_interactions();
+        // the frame of the release still sees where it was, for clicks and drops.
+        // without a finger on the screen nothing is hovered after that
+        if let Some(pos) = self.touches.primary_lifted.take()
+            && self.mouse_pos == pos
+        {
+            self.mouse_pos = Vec2::INFINITY;
+        }
+
+        // clear unused buffers then set them all to unused
+        self.cached_buffers.retain(|_, (_, in_use)| *in_use);
+        for (_, in_use) in self.cached_buffers.values_mut() {
+            *in_use = false;
+        }
+        // clear unused temp states then set them all to unused
+        self.temp_states.retain(|_, (_, in_use)| *in_use);
+        for (_, in_use) in self.temp_states.values_mut() {
+            *in_use = false;
+        }
+    }
+    /// a pass before the real one that only collects the senses,
+    /// so the real one can test against where they are this frame
+    pub(crate) fn start_layout_pass(&mut self) {
+        self.reset();
+        self.layout_pass = true;
+
+        // the events of the last frame were already seen, the new ones are for the real pass
+        self.keyboard.frame = KeyChanges::default();
+        self.mouse_buttons.pressed = ButtonMap::default();
+        self.mouse_buttons.released = ButtonMap::default();
+        self.touches.frame.clear();
+        self.interactions.scroll = Scroll::default();
+        self.interactions.gestures = Gestures::default();
+    }
+    fn reset(&mut self) {
         self.instances.clear();
-        self.clip_polygon_points.clear();
-        self.clip_polygons.clear();
-        // there need to be some items in
-        self.clip_polygon_points.push([0.0; 2]);
-        self.clip_polygons
-            .push(wgsl_main::structs::ClipPolygon::new(0, 0, 0));
+        swap(&mut self.clips, &mut self.old_clips);
+        self.clips.clear();
 
         self.render_passes.clear();
         self.render_passes.push(RenderPass {
@@ -156,9 +421,39 @@ impl Stage {
                 start_instance: 0,
                 set_blend_mode: None,
                 set_texture: None,
+                set_mask: None,
             }],
         });
 
+        // the render pass starts out with these
+        self.current_blend_mode = BlendMode::Normal;
+        self.current_mask_texture = None;
+        self.reset_draw_state();
+
+        self.ime_caret = None;
+        self.deferred.clear();
+        self.pick_senses.clear();
+
+        swap(&mut self.build_senses, &mut self.old_senses);
+        self.build_senses.clear();
+        swap(&mut self.build_sense_points, &mut self.old_sense_points);
+        self.build_sense_points.clear();
+        self.old_sense_grid
+            .rebuild(&self.old_senses, &self.old_sense_points);
+        self.sense_parents.clear();
+        self.sense_parents.extend(
+            self.old_senses
+                .iter()
+                .filter(|v| !v.options.consume_clicks)
+                .filter_map(|v| Some((v.id, v.options.parent?.0))),
+        );
+
+        self.sense_id_ctr = 0;
+        debug_assert!(self.id_stack.is_empty(), "`push_id` without `pop_id`");
+        self.id_stack.clear();
+    }
+    /// what drawing starts with every frame, and every deferred draw
+    fn reset_draw_state(&mut self) {
         self.fill_color = Color::rgb8(255, 255, 255);
         self.stroke_color = Color::rgb8(255, 255, 255);
         self.stroke_weight = 2.0;
@@ -167,35 +462,33 @@ impl Stage {
         self.draw_stroke = false;
 
         self.arc_segments = 8;
+        self.clip_tolerance = 0.25;
 
         self.transform = Affine2::IDENTITY;
 
-        self.current_blend_mode = BlendMode::Normal;
+        self.set_blend_mode(BlendMode::Normal);
         self.current_texture = None;
-        self.current_clip = 0;
-
-        swap(&mut self.build_senses, &mut self.old_senses);
-        self.build_senses.clear();
-
-        self.sense_id_ctr = 0;
-
-        self.update_interactions();
-
-        // clear unused buffers then set them all to unused
-        self.cached_buffers.retain(|_, (_, in_use)| *in_use);
-        for (_, in_use) in self.cached_buffers.values_mut() {
-            *in_use = false;
-        }
-        // clear unused temp states then set them all to unused
-        self.temp_states.retain(|_, (_, in_use)| *in_use);
-        for (_, in_use) in self.temp_states.values_mut() {
-            *in_use = false;
-        }
+        self.set_current_clip(0);
+        self.current_pick = NO_PICK;
     }
     pub(crate) fn update_interactions(&mut self) {
-        let old = self.interactions;
-
-        self.interactions.hovering = self.find_top_old_sense().map(|v| v.id);
+        let old = self.interactions.clone();
+        let mouse_down = self.mouse_buttons.held_senses.get(MouseButton::Left);
+
+        self.senses_under_mouse = self.old_senses_at(self.mouse_pos).map(|v| v.id).collect();
+        let pass_through = self
+            .old_senses_at(self.mouse_pos)
+            .take_while(|v| v.options.pass_through)
+            .map(|v| v.id)
+            .collect();
+        self.old_pass_through_hovering = replace(&mut self.pass_through_hovering, pass_through);
+
+        // the dragged sense captures the mouse
+        self.interactions.hovering = if old.dragging.is_some() && old.dragging == mouse_down {
+            old.dragging
+        } else {
+            self.find_top_old_sense().map(|v| v.id)
+        };
         self.interactions.hover_started = if self.interactions.hovering != old.hovering {
             self.interactions.hovering
         } else {
@@ -206,75 +499,250 @@ impl Stage {
         } else {
             None
         };
-
-        self.interactions.holding = self.mouse_down;
-        self.interactions.click_started = if self.interactions.holding != old.holding {
-            self.interactions.holding
+        if self.interactions.hovering != old.hovering {
+            self.hover_time = self.time;
+        }
+        self.interactions.hover_duration = if self.interactions.hovering.is_some() {
+            self.time - self.hover_time
+        } else {
+            Duration::ZERO
+        };
+        self.interactions.hover_delay_elapsed = self
+            .interactions
+            .hovering
+            .filter(|_| self.interactions.hover_duration >= self.hover_delay);
+
+        let mut buttons_click_started = ButtonMap::default();
+        let mut buttons_click_ended = ButtonMap::default();
+        let held = &self.mouse_buttons.held_senses;
+        for button in held.buttons().chain(old.buttons_holding.buttons()).unique() {
+            let holding = held.get(button);
+            let old_holding = old.buttons_holding.get(button);
+            if holding != old_holding {
+                buttons_click_started.set(button, holding);
+                buttons_click_ended.set(button, old_holding);
+            }
+        }
+        self.interactions.buttons_holding = held.clone();
+        self.interactions.buttons_click_started = buttons_click_started;
+        self.interactions.buttons_click_ended = buttons_click_ended;
+        self.interactions.holding = self.interactions.buttons_holding.get(MouseButton::Left);
+        self.interactions.click_started = self
+            .interactions
+            .buttons_click_started
+            .get(MouseButton::Left);
+        self.interactions.click_ended =
+            self.interactions.buttons_click_ended.get(MouseButton::Left);
+
+        let drag_offset = self.mouse_pos - self.mouse_down_pos;
+        self.interactions.dragging = if old.dragging.is_some() && old.dragging == mouse_down {
+            old.dragging
+        } else if drag_offset.length() >= self.drag_threshold {
+            mouse_down
         } else {
             None
         };
-        self.interactions.click_ended = if self.interactions.holding != old.holding {
-            old.holding
+        self.interactions.drag_started = if self.interactions.dragging != old.dragging {
+            self.interactions.dragging
         } else {
             None
         };
-
-        self.interactions.right_holding = self.right_mouse_down;
-        self.interactions.right_click_started =
-            if self.interactions.right_holding != old.right_holding {
-                self.interactions.right_holding
-            } else {
-                None
-            };
-        self.interactions.right_click_ended =
-            if self.interactions.right_holding != old.right_holding {
-                old.right_holding
+        self.interactions.drag_ended = if self.interactions.dragging != old.dragging {
+            old.dragging
+        } else {
+            None
+        };
+        if self.interactions.dragging.is_some() {
+            // the first delta includes the threshold, so the deltas add up to the offset
+            let delta = if self.interactions.drag_started.is_some() {
+                drag_offset
             } else {
-                None
+                self.mouse_pos - self.last_mouse_pos
             };
+            self.interactions.drag_delta = delta.to_array();
+            self.interactions.drag_offset = drag_offset.to_array();
+        } else {
+            self.interactions.drag_delta = [0.0; 2];
+            self.interactions.drag_offset = [0.0; 2];
+        }
+        self.last_mouse_pos = self.mouse_pos;
+
+        self.update_focus(old.focused);
+
+        self.interactions.click_count = self.click_count;
+        self.interactions.long_pressed = if self.interactions.holding.is_some()
+            && self.interactions.dragging.is_none()
+            && !self.long_press_done
+            && self.time - self.mouse_down_time >= self.long_press_duration
+        {
+            self.long_press_done = true;
+            self.interactions.holding
+        } else {
+            None
+        };
+
+        // the dragged thing is probably under the mouse, so look below it
+        let carried = self
+            .drag_payload
+            .as_ref()
+            .map(|(id, _)| *id)
+            .filter(|id| old.dragging == Some(*id));
+        let drop_target = carried.and_then(|carried| {
+            self.find_top_old_sense_where(self.mouse_pos, |sense| sense.id != carried)
+                .map(|v| v.id)
+        });
+        self.interactions.drop_hovered =
+            drop_target.filter(|_| self.interactions.dragging.is_some());
+        self.interactions.dropped = drop_target.filter(|_| self.interactions.drag_ended.is_some());
+        if self.interactions.dragging.is_none() && self.interactions.dropped.is_none() {
+            // kept for the frame of the drop so the target can take it
+            self.drag_payload = None;
+        }
+
+        self.interactions.scroll = take(&mut self.next_scroll);
+        self.interactions.gestures = take(&mut self.next_gestures);
     }
 
-    pub fn add_clip(&mut self, path: &Path) {
-        let start_point = self.clip_polygon_points.len();
+    fn push_clip(
+        &mut self,
+        start: usize,
+        end: usize,
+        op: u32,
+        kind: u32,
+        mask_texture: Option<TextureKey>,
+    ) {
+        // `add_mask` made sure they don't conflict
+        let parent_mask_texture = self.clips.mask_textures[self.current_clip as usize];
 
-        walk::walk_along_path(
-            &path.inner,
-            0.0,
-            0.1,
-            &mut walk::RegularPattern {
-                callback: |event: walk::WalkerEvent| {
-                    let pos = self
-                        .transform
-                        .transform_point2(vec2(event.position.x, event.position.y));
+        self.clips.polygons.push(make_polygon(
+            self.transform,
+            start as u32,
+            end as u32,
+            self.current_clip,
+            op,
+            kind,
+        ));
+        self.clips
+            .mask_textures
+            .push(mask_texture.or(parent_mask_texture));
 
-                    self.clip_polygon_points.push([pos.x, pos.y]);
-                    true
-                },
-                interval: 5.0,
-            },
-        );
+        self.set_current_clip(self.clips.polygons.len() as u32 - 1);
+    }
+    fn set_current_clip(&mut self, clip: u32) {
+        self.current_clip = clip;
+
+        let mask_texture = self.clips.mask_textures[clip as usize];
+        if mask_texture.is_some() && self.current_mask_texture != mask_texture {
+            let calls = &mut self.render_passes.last_mut().unwrap().draw_calls;
+            calls.push(DrawCall {
+                start_instance: self.instances.len() as u32,
+                set_blend_mode: None,
+                set_texture: None,
+                set_mask: mask_texture,
+            });
+            self.current_mask_texture = mask_texture;
+        }
+    }
 
-        let end_point = self.clip_polygon_points.len();
-        self.clip_polygons
-            .push(wgsl_main::structs::ClipPolygon::new(
-                start_point as u32,
-                end_point as u32,
-                self.current_clip,
-            ));
-        self.current_clip = self.clip_polygons.len() as u32 - 1;
+    /// `clip_tolerance` is in pixels, paths are flattened before the transform.
+    /// `None` if the transform squashes everything flat, there is nothing to flatten then
+    fn local_tolerance(&self) -> Option<f32> {
+        let scale = self
+            .transform
+            .matrix2
+            .x_axis
+            .length()
+            .max(self.transform.matrix2.y_axis.length());
+        let tolerance = self.clip_tolerance.max(MIN_CLIP_TOLERANCE) / scale;
+        (!is_degenerate(self.transform) && tolerance.is_finite() && tolerance > 0.0)
+            .then_some(tolerance)
+    }
+
+    pub fn add_clip(&mut self, path: &Path, op: ClipOp) {
+        let start_point = self.clips.points.len();
+        if let Some(tolerance) = self.local_tolerance() {
+            path.flatten_into(tolerance, &mut self.clips.points);
+        }
+        let end_point = self.clips.points.len();
+        self.push_clip(start_point, end_point, op.to_u32(), CLIP_KIND_POLYGON, None);
+    }
+    /// clips by the alpha of `texture` stretched over `rect` (x, y, w, h), nothing is drawn outside of it.
+    ///
+    /// all masks in a clip chain have to use the same texture, since only one can be bound at a time.
+    /// if the chain already uses another one nothing is added
+    pub fn add_mask(
+        &mut self,
+        texture: TextureInfo,
+        rect: [f32; 4],
+        op: ClipOp,
+    ) -> Result<(), MaskTextureConflict> {
+        let parent_mask_texture = self.clips.mask_textures[self.current_clip as usize];
+        if parent_mask_texture.is_some_and(|v| v != texture.key) {
+            return Err(MaskTextureConflict);
+        }
+        let [x, y, w, h] = rect;
+
+        let start = self.clips.masks.len();
+        self.clips.masks.push(wgsl_main::structs::ClipMask::new(
+            [x, y],
+            [x + w, y + h],
+            [0.0, 1.0],
+            [1.0, 0.0],
+            0,
+            3,
+        ));
+        self.push_clip(
+            start,
+            start + 1,
+            op.to_u32(),
+            CLIP_KIND_MASK,
+            Some(texture.key),
+        );
+        Ok(())
+    }
+    /// flips the current clip, so that only what was previously clipped away is drawn
+    pub fn invert_clip(&mut self) {
+        self.push_clip(0, 0, CLIP_OP_INVERT, CLIP_KIND_POLYGON, None);
     }
     pub fn get_clip_id(&self) -> ClipID {
         ClipID(self.current_clip)
     }
     pub fn set_clip_id(&mut self, id: ClipID) {
-        self.current_clip = id.0;
+        self.set_current_clip(id.0);
     }
 
     fn new_sense_id(&mut self) -> u64 {
+        // in a scope the ids only shift when the senses in that scope change
+        if let Some((scope, ctr)) = self.id_stack.last_mut() {
+            let v = hash_key(&(*scope, *ctr));
+            *ctr += 1;
+            return v;
+        }
         let v = self.sense_id_ctr;
         self.sense_id_ctr += 1;
         v
     }
+    /// the keyed id if there is a key, else the next one in the current scope
+    fn sense_id(&mut self, key: Option<u64>) -> u64 {
+        match key {
+            Some(key) => self.keyed_sense_id(key),
+            None => self.new_sense_id(),
+        }
+    }
+    fn keyed_sense_id(&self, key: u64) -> u64 {
+        let scope = self.id_stack.last().map(|(scope, _)| *scope);
+        hash_key(&(scope, key))
+    }
+
+    /// starts a scope for ids, senses and scopes in it only need keys that are unique in it.
+    /// has to be closed with `pop_id`
+    pub fn push_id(&mut self, key: impl Hash + 'static) {
+        let scope = self.keyed_sense_id(hash_key(&key));
+        self.id_stack.push((scope, 0));
+    }
+    pub fn pop_id(&mut self) {
+        self.id_stack.pop();
+    }
 
     pub fn temp<K: Hash + 'static, T: 'static, F: FnOnce() -> T>(
         &mut self,
@@ -292,18 +760,139 @@ impl Stage {
         val.downcast_mut().unwrap()
     }
 
+    /// attaches a payload to what is currently dragged, call it when `drag_started`.
+    /// the senses it gets dragged over report `drop_hovered` and the one it's released on `dropped`
+    pub fn set_drag_payload<T: 'static>(&mut self, payload: T) {
+        if let Some(id) = self.interactions.dragging {
+            self.drag_payload = Some((id, Box::new(payload)));
+        }
+    }
+    /// the payload of the current drag, or the one that was just dropped
+    pub fn drag_payload<T: 'static>(&self) -> Option<&T> {
+        self.drag_payload
+            .as_ref()
+            .and_then(|(_, payload)| payload.downcast_ref())
+    }
+    /// same as `drag_payload` but removes it, if it's a `T`
+    pub fn take_drag_payload<T: 'static>(&mut self) -> Option<T> {
+        if !self.drag_payload.as_ref()?.1.is::<T>() {
+            return None;
+        }
+        let (_, payload) = self.drag_payload.take()?;
+        payload.downcast().ok().map(|v| *v)
+    }
+
+    /// seconds since the last frame, 0 in the layout pass so nothing moves twice
     pub fn delta(&self) -> f64 {
-        self.delta
+        if self.layout_pass { 0.0 } else { self.delta }
     }
+    /// the left button is down, anywhere
     pub fn mouse_down(&self) -> bool {
-        self.mouse_down.is_some()
+        self.mouse_button_down(MouseButton::Left)
     }
+    #[deprecated = "use `mouse_button_down(MouseButton::Right)`"]
     pub fn right_mouse_down(&self) -> bool {
-        self.right_mouse_down.is_some()
+        self.mouse_button_down(MouseButton::Right)
+    }
+    pub fn mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.down.get(button)
+    }
+    /// pressed since the last frame
+    pub fn mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.pressed.get(button)
+    }
+    /// released since the last frame
+    pub fn mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.released.get(button)
     }
     pub fn mouse_world_pos(&self) -> [f32; 2] {
         self.mouse_pos.to_array()
     }
+    /// the first pass of `same_frame_interactions`, nothing drawn in it is shown.
+    /// things that don't add senses can be skipped in it
+    pub fn is_layout_pass(&self) -> bool {
+        self.layout_pass
+    }
+    /// in pixels, the world goes from minus half of it to plus half of it
+    pub fn window_size(&self) -> [f32; 2] {
+        self.window_size.to_array()
+    }
+    /// all touches that are down, or were lifted since the last frame
+    pub fn touches(&self) -> &[Touch] {
+        &self.touches.frame
+    }
+    /// everything scrolled since the last frame, no matter what is hovered
+    pub fn scroll(&self) -> Scroll {
+        self.interactions.scroll
+    }
+    /// all trackpad gestures since the last frame, no matter what is hovered
+    pub fn gestures(&self) -> Gestures {
+        self.interactions.gestures
+    }
+
+    // logical keys are what the key means with the current layout and modifiers, like `Key::Character("A")`
+    pub fn key_down(&self, key: impl Into<Key>) -> bool {
+        let key = key.into();
+        self.keyboard.down.values().any(|k| *k == key)
+    }
+    pub fn key_pressed(&self, key: impl Into<Key>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.pressed.iter().any(|(_, k)| *k == key)
+    }
+    pub fn key_released(&self, key: impl Into<Key>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.released.iter().any(|(_, k)| *k == key)
+    }
+    /// the os sent a repeat for the held key this frame, doesn't count the first press
+    pub fn key_repeated(&self, key: impl Into<Key>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.repeated.iter().any(|(_, k)| *k == key)
+    }
+    // physical keys are the position on the keyboard, like `KeyCode::KeyW`
+    pub fn physical_key_down(&self, key: impl Into<PhysicalKey>) -> bool {
+        self.keyboard.down.contains_key(&key.into())
+    }
+    pub fn physical_key_pressed(&self, key: impl Into<PhysicalKey>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.pressed.iter().any(|(k, _)| *k == key)
+    }
+    pub fn physical_key_released(&self, key: impl Into<PhysicalKey>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.released.iter().any(|(k, _)| *k == key)
+    }
+    pub fn physical_key_repeated(&self, key: impl Into<PhysicalKey>) -> bool {
+        let key = key.into();
+        self.keyboard.frame.repeated.iter().any(|(k, _)| *k == key)
+    }
+    pub fn modifiers(&self) -> ModifiersState {
+        self.keyboard.modifiers
+    }
+    /// everything typed since the last frame, in order
+    pub fn text_input(&self) -> &[TextInput] {
+        &self.keyboard.frame.text
+    }
+    /// the text the ime is currently composing and the byte range of its cursor
+    pub fn ime_preedit(&self) -> Option<(&str, Option<(usize, usize)>)> {
+        self.keyboard
+            .preedit
+            .as_ref()
+            .map(|(text, cursor)| (text.as_str(), *cursor))
+    }
+    /// allows the ime for this frame, with the candidate window placed next to the caret (x, y, w, h).
+    /// call it every frame while a text field has focus
+    pub fn request_ime(&mut self, caret: [f32; 4]) {
+        let [x, y, w, h] = caret;
+        let corners = [
+            vec2(x, y),
+            vec2(x + w, y),
+            vec2(x, y + h),
+            vec2(x + w, y + h),
+        ]
+        .map(|p| self.transform.transform_point2(p));
+        let min = corners.into_iter().reduce(Vec2::min).unwrap();
+        let max = corners.into_iter().reduce(Vec2::max).unwrap();
+        self.ime_caret = Some([min, max]);
+    }
 
     pub fn draw_stroke(&mut self, points: impl ExactSizeIterator<Item = [f32; 2]> + Clone) {
         let n_verts = points.len() as u32 * 2;
@@ -380,6 +969,7 @@ impl Stage {
                 start_instance: self.instances.len() as u32,
                 set_blend_mode: Some(mode),
                 set_texture: None,
+                set_mask: None,
             });
             self.current_blend_mode = mode;
         }
@@ -394,6 +984,7 @@ impl Stage {
                 start_instance: self.instances.len() as u32,
                 set_blend_mode: None,
                 set_texture: Some(texture.key),
+                set_mask: None,
             });
             self.current_texture = Some(texture);
         }
@@ -402,14 +993,379 @@ impl Stage {
         self.current_texture
     }
 
-    pub(crate) fn find_top_old_sense(&self) -> Option<SenseSave> {
-        for sense in self.old_senses.iter().rev() {
-            if test_in_shape(sense.shape, self.mouse_pos) {
-                return Some(*sense);
+    fn update_focus(&mut self, old_focused: Option<u64>) {
+        let focusable = |id: u64| {
+            self.old_senses
+                .iter()
+                .any(|v| v.id == id && v.options.focus_order.is_some())
+        };
+
+        // drop it if the sense is gone
+        let mut focused = old_focused.filter(|id| focusable(*id));
+
+        // clicking anywhere else clears it, clicking a child focuses the parent
+        if self.mouse_buttons.pressed.get(MouseButton::Left) {
+            focused = self
+                .click_chain(self.interactions.click_started)
+                .find(|id| focusable(*id));
+        }
+        let mut request = self.focus_request.take();
+        if self.tab_navigation
+            && self
+                .keyboard
+                .frame
+                .pressed
+                .iter()
+                .chain(&self.keyboard.frame.repeated)
+                .any(|(_, k)| *k == Key::Named(NamedKey::Tab))
+        {
+            request = Some(if self.keyboard.modifiers.shift_key() {
+                FocusRequest::Previous
+            } else {
+                FocusRequest::Next
+            });
+        }
+        if let Some(request) = request {
+            focused = match request {
+                FocusRequest::Sense(id) => Some(id).filter(|id| focusable(*id)),
+                FocusRequest::Clear => None,
+                FocusRequest::Next | FocusRequest::Previous => {
+                    let order = self
+                        .old_senses
+                        .iter()
+                        .filter(|v| v.options.focus_order.is_some())
+                        .sorted_by_key(|v| v.options.focus_order)
+                        .map(|v| v.id)
+                        .collect_vec();
+                    let current = focused.and_then(|id| order.iter().position(|v| *v == id));
+                    let idx = match (request, current) {
+                        (FocusRequest::Next, Some(idx)) => Some((idx + 1) % order.len()),
+                        (FocusRequest::Next, None) => Some(0),
+                        (_, Some(idx)) => Some((idx + order.len() - 1) % order.len()),
+                        (_, None) => order.len().checked_sub(1),
+                    };
+                    idx.and_then(|idx| order.get(idx).copied())
+                }
+            };
+        }
+
+        self.interactions.focused = focused;
+        self.interactions.focus_gained = if focused != old_focused {
+            focused
+        } else {
+            None
+        };
+        self.interactions.focus_lost = if focused != old_focused {
+            old_focused
+        } else {
+            None
+        };
+    }
+
+    /// focuses the sense from the next frame on, if it has a `focus_order` then
+    pub fn focus(&mut self, id: SenseID) {
+        self.focus_request = Some(FocusRequest::Sense(id.0));
+    }
+    /// focuses the sense that was added last, from the next frame on
+    pub fn focus_last_sense(&mut self) {
+        if let Some(id) = self.last_sense_id() {
+            self.focus(id);
+        }
+    }
+    pub fn focus_next(&mut self) {
+        self.focus_request = Some(FocusRequest::Next);
+    }
+    pub fn focus_previous(&mut self) {
+        self.focus_request = Some(FocusRequest::Previous);
+    }
+    pub fn clear_focus(&mut self) {
+        self.focus_request = Some(FocusRequest::Clear);
+    }
+
+    /// draws on top of everything else once the frame is done.
+    /// it starts like a frame does, without any transform, clip, texture or pick sense
+    pub fn defer(&mut self, draw: impl FnOnce(&mut Stage, &mut AppData) + 'static) {
+        let draw: DeferredDraw = Box::new(draw);
+        self.deferred.push(draw);
+    }
+    pub(crate) fn finish(&mut self, app_data: &mut AppData) {
+        // deferred draws can defer more
+        while !self.deferred.is_empty() {
+            for draw in take(&mut self.deferred) {
+                self.reset_draw_state();
+                draw(self, app_data);
+            }
+        }
+    }
+
+    /// draws `content` next to the mouse once the sense has been hovered for `hover_delay`.
+    /// it's deferred so it ends up on top, and moved to stay inside the window.
+    /// `content` draws from (0, 0) to `size`
+    pub fn tooltip(
+        &mut self,
+        interactions: &Interactions<bool>,
+        size: [f32; 2],
+        content: impl FnOnce(&mut Stage, &mut AppData) + 'static,
+    ) {
+        if !interactions.hover_delay_elapsed {
+            return;
+        }
+        let size = Vec2::from(size);
+        let half = self.window_size / 2.0;
+        // how far it is from the mouse, so the cursor doesn't cover it
+        let gap = 16.0;
+
+        // below and to the right, or flipped if there is no room
+        let mut pos = self.mouse_pos + vec2(gap, -gap - size.y);
+        if pos.x + size.x > half.x {
+            pos.x = self.mouse_pos.x - gap - size.x;
+        }
+        if pos.y < -half.y {
+            pos.y = self.mouse_pos.y + gap;
+        }
+        let pos = pos.clamp(-half, (half - size).max(-half));
+
+        self.defer(move |stage, app_data| {
+            stage.translate(pos.x, pos.y);
+            content(stage, app_data);
+        });
+    }
+
+    /// the cursor of the hovered sense, the dragged one keeps its cursor
+    pub(crate) fn cursor(&self) -> Cursor {
+        self.interactions
+            .hovering
+            .and_then(|id| self.old_senses.iter().find(|v| v.id == id))
+            .and_then(|v| v.options.cursor)
+            .unwrap_or(self.default_cursor)
+    }
+
+    /// feeds an event to the stage like the window does, so it can be used without one.
+    /// it counts for the next frame. ignored while a replay is running
+    pub fn input(&mut self, event: InputEvent) {
+        if self.replay.is_empty() {
+            self.apply_input(event);
+        }
+    }
+    fn apply_input(&mut self, event: InputEvent) {
+        if self.recording.is_some() {
+            self.recording_events.push(event.clone());
+        }
+
+        match event {
+            InputEvent::MouseMoved(pos) => self.mouse_pos = pos.into(),
+            InputEvent::MousePressed(button) => self.press_mouse(button),
+            InputEvent::MouseReleased(button) => self.release_mouse(button),
+            InputEvent::Touch { id, pos, phase } => self.touch_event(id, pos.into(), phase),
+            InputEvent::ScrollLines([x, y]) => {
+                self.next_scroll.lines[0] += x;
+                self.next_scroll.lines[1] += y;
+            }
+            InputEvent::ScrollPixels([x, y]) => {
+                self.next_scroll.pixels[0] += x;
+                self.next_scroll.pixels[1] += y;
+            }
+            InputEvent::Zoom(zoom) => self.next_gestures.zoom *= zoom,
+            InputEvent::Rotate(angle) => self.next_gestures.rotation += angle,
+            InputEvent::Pan([x, y]) => {
+                self.next_gestures.pan[0] += x;
+                self.next_gestures.pan[1] += y;
+            }
+            InputEvent::Key {
+                physical,
+                logical,
+                text,
+                pressed,
+                repeat,
+            } => self
+                .keyboard
+                .key_event(physical, logical, text.as_deref(), pressed, repeat),
+            InputEvent::Ime(ime) => self.keyboard.ime_event(ime),
+            InputEvent::Modifiers(modifiers) => self.keyboard.modifiers = modifiers,
+            InputEvent::FocusLost => self.keyboard.release_all(),
+        }
+    }
+
+    /// ends the frame and starts the next one like the window does, for using the stage without one.
+    /// `delta` is in seconds, replays use their own.
+    /// deferred draws are dropped, without the gpu there is nothing to draw them with
+    pub fn next_frame(&mut self, delta: f64) {
+        self.delta = delta;
+        self.apply_replay();
+        self.start();
+    }
+    /// gives the input of the next replayed frame to the stage, call before `start`
+    pub(crate) fn apply_replay(&mut self) {
+        let Some(frame) = self.replay.pop_front() else {
+            return;
+        };
+        self.delta = frame.delta;
+        self.window_size = frame.window_size.into();
+        for event in frame.events {
+            self.apply_input(event);
+        }
+    }
+
+    /// records the input of every frame from the next one on, until `stop_recording`
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+        self.recording_events.clear();
+    }
+    /// the frames recorded so far, the input since the last one started is dropped
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording_events.clear();
+        self.recording.take()
+    }
+    /// plays the recorded input back frame by frame, with the deltas and window size it was recorded with.
+    /// for it to behave the same the stage should be in the same state as when recording started
+    pub fn replay(&mut self, recording: Recording) {
+        self.replay = recording.frames.into();
+    }
+    pub fn is_replaying(&self) -> bool {
+        !self.replay.is_empty()
+    }
+
+    fn press_mouse(&mut self, button: MouseButton) {
+        let id = self.find_top_old_sense().map(|v| v.id);
+        self.mouse_buttons.press(button, id);
+
+        // clicks are counted and dragged with the left button only
+        if button != MouseButton::Left {
+            return;
+        }
+        let now = self.time;
+
+        let repeated = self.last_press.is_some_and(|(last_id, time, pos)| {
+            id == Some(last_id)
+                && now - time <= self.double_click_interval
+                && pos.distance(self.mouse_pos) <= self.double_click_slop
+        });
+        self.click_count = if repeated { self.click_count + 1 } else { 1 };
+        self.last_press = id.map(|id| (id, now, self.mouse_pos));
+
+        self.mouse_down_pos = self.mouse_pos;
+        self.mouse_down_time = now;
+        self.long_press_done = false;
+    }
+
+    fn release_mouse(&mut self, button: MouseButton) {
+        self.mouse_buttons.release(button);
+    }
+    /// lets go of the left button without it counting as a click or a drop,
+    /// the next frame sees nothing held or dragged before either
+    fn cancel_mouse(&mut self) {
+        self.release_mouse(MouseButton::Left);
+        self.interactions.holding = None;
+        self.interactions
+            .buttons_holding
+            .set(MouseButton::Left, None);
+        self.interactions.dragging = None;
+    }
+
+    fn touch_event(&mut self, id: u64, pos: Vec2, phase: TouchPhase) {
+        // a single touch acts as the mouse, so everything made for one works with it
+        if phase == TouchPhase::Started && self.touches.primary.is_none() {
+            self.touches.primary = Some(id);
+            self.touches.primary_lifted = None;
+        }
+        let primary = self.touches.primary == Some(id);
+        // moved first so everything below sees the touch where it is now
+        if primary {
+            self.mouse_pos = pos;
+        }
+
+        let sense = if phase == TouchPhase::Started {
+            // pick senses are only tested at the mouse
+            self.find_top_old_sense_where(pos, |sense| {
+                !matches!(sense.shape.typ, SenseShapeType::Pick)
+            })
+            .map(|v| v.id)
+        } else {
+            None
+        };
+        self.touches.event(id, pos, phase, sense);
+
+        if primary {
+            match phase {
+                TouchPhase::Started => self.press_mouse(MouseButton::Left),
+                TouchPhase::Moved => {}
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    if phase == TouchPhase::Cancelled {
+                        self.cancel_mouse();
+                    } else {
+                        self.release_mouse(MouseButton::Left);
+                    }
+                    self.touches.primary = None;
+                    self.touches.primary_lifted = Some(pos);
+                }
+            }
+        }
+    }
+
+    /// the id of the sense that was added last, to use it as a parent
+    pub fn last_sense_id(&self) -> Option<SenseID> {
+        self.build_senses.last().map(|v| SenseID(v.id))
+    }
+    /// all senses that were under the mouse last frame, the top one first.
+    /// includes the pass through ones and the ones covered by others
+    pub fn senses_under_mouse(&self) -> impl Iterator<Item = SenseID> {
+        self.senses_under_mouse.iter().map(|id| SenseID(*id))
+    }
+
+    /// what is drawn after this isn't part of the last pick sense anymore, but still covers it
+    pub fn end_pick_sense(&mut self) {
+        self.current_pick = NO_PICK;
+    }
+
+    /// the sense and the parents its clicks go up to
+    fn click_chain(&self, id: Option<u64>) -> impl Iterator<Item = u64> {
+        // bounded in case keyed senses made a cycle
+        successors(id, |id| self.sense_parents.get(id).copied()).take(self.sense_parents.len() + 1)
+    }
+
+    /// the senses from the last frame at `pos`, the top one first
+    fn old_senses_at(&self, pos: Vec2) -> impl Iterator<Item = &SenseSave> {
+        self.old_sense_candidates(pos)
+            .filter(move |sense| self.old_sense_at(sense, pos))
+    }
+    fn old_sense_at(&self, sense: &SenseSave, pos: Vec2) -> bool {
+        match sense.shape.typ {
+            // the gpu already took the clips and what covers it into account,
+            // it's only rendered at the mouse
+            SenseShapeType::Pick => self.picked_sense == Some(sense.id) && pos == self.mouse_pos,
+            _ => {
+                test_in_shape(sense.shape, &self.old_sense_points, pos)
+                    && self.old_clips.contains(sense.clip, pos)
             }
         }
-        None
     }
+    /// the senses from the last frame that might be at `pos`, the top one first
+    fn old_sense_candidates(&self, pos: Vec2) -> impl Iterator<Item = &SenseSave> {
+        self.old_sense_grid
+            .query(pos)
+            .map(|idx| &self.old_senses[idx])
+    }
+    /// the top sense that blocks the ones below it
+    pub(crate) fn find_top_old_sense(&self) -> Option<SenseSave> {
+        self.find_top_old_sense_where(self.mouse_pos, |_| true)
+    }
+    fn find_top_old_sense_where(
+        &self,
+        pos: Vec2,
+        filter: impl Fn(&SenseSave) -> bool,
+    ) -> Option<SenseSave> {
+        self.old_sense_candidates(pos)
+            .find(|v| !v.options.pass_through && filter(v) && self.old_sense_at(v, pos))
+            .copied()
+    }
+}
+
+/// hashes with the type too, so keys of different types don't collide
+fn hash_key<K: Hash + 'static>(key: &K) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<K>().hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
 }
 
 // MARK: Draw builders
@@ -443,6 +1399,7 @@ impl Stage {
             self.transform.translation.to_array(),
             0,
             self.current_clip,
+            self.current_pick,
         ));
     }
 
@@ -644,6 +1601,8 @@ impl Stage {
         #[builder(default = cosmic_text::Weight::NORMAL)] weight: cosmic_text::Weight,
         #[builder(default = cosmic_text::Style::Normal)] style: cosmic_text::Style,
         #[builder(default = cosmic_text::Stretch::Normal)] stretch: cosmic_text::Stretch,
+        /// instead of drawing the text, add it to the current clip as a mask
+        mask: Option<ClipOp>,
     ) {
         let metrics = cosmic_text::Metrics::relative(size, line_height);
         let attrs = AttrsOwned::new(&find_closest_attrs(
@@ -676,6 +1635,8 @@ impl Stage {
         buffer.set_size(&mut app_data.gpu_data.font_system, w, h);
         buffer.shape_until_scroll(&mut app_data.gpu_data.font_system, true);
 
+        let mask_start = self.clips.masks.len();
+
         for run in buffer.layout_runs() {
             for glyph in run.glyphs {
                 let physical = glyph.physical((0.0, 0.0), 1.0);
@@ -689,40 +1650,182 @@ impl Stage {
                     x,
                     y,
                     self.current_clip,
+                    self.current_pick,
                 ) {
-                    self.instances.extend(instances);
+                    if mask.is_some() {
+                        self.clips.masks.push(mask_from_glyph(instances));
+                    } else {
+                        self.instances.extend(instances);
+                    }
                     // self.push_rect_direct(rect);
                 }
             }
         }
+
+        if let Some(op) = mask {
+            let mask_end = self.clips.masks.len();
+            self.push_clip(mask_start, mask_end, op.to_u32(), CLIP_KIND_MASK, None);
+        }
     }
 }
 
 // MARK: Sense builders
 #[bon::bon]
 impl Stage {
-    fn add_sense(&mut self, shape: SenseShape, id: u64) -> Interactions<bool> {
-        self.build_senses.push(SenseSave { shape, id });
-
-        let in_shape = test_in_shape(shape, self.mouse_pos);
+    fn add_sense(
+        &mut self,
+        shape: SenseShape,
+        id: u64,
+        options: SenseOptions,
+    ) -> Interactions<bool> {
+        let clip = if options.ignore_clip {
+            0
+        } else {
+            self.current_clip
+        };
+        self.build_senses.push(SenseSave {
+            shape,
+            id,
+            clip,
+            options,
+        });
+        // the same interactions as last frame, so the same senses are added as in the real pass
+        if self.layout_pass {
+            return self.last_interactions.get(&id).cloned().unwrap_or_default();
+        }
 
-        Interactions {
-            hovering: self.interactions.hovering.is_some_and(|v| v == id),
-            hover_started: self.interactions.hover_started.is_some_and(|v| v == id),
-            hover_ended: self.interactions.hover_ended.is_some_and(|v| v == id),
-            hovering_bypass: in_shape,
+        let in_shape = match shape.typ {
+            SenseShapeType::Pick => self.picked_sense == Some(id),
+            _ => {
+                test_in_shape(shape, &self.build_sense_points, self.mouse_pos)
+                    && self.clips.contains(clip, self.mouse_pos)
+            }
+        };
 
-            holding: self.interactions.holding.is_some_and(|v| v == id),
-            click_started: self.interactions.click_started.is_some_and(|v| v == id),
-            click_ended: self.interactions.click_ended.is_some_and(|v| v == id),
+        let dragging = self.interactions.dragging.is_some_and(|v| v == id);
+        let drag_delta = Vec2::from(self.interactions.drag_delta);
+        let drag_offset = Vec2::from(self.interactions.drag_offset);
+        let local = |v: Vec2| shape.inv_transform.transform_vector2(v).to_array();
+        let mouse_local_pos = shape.inv_transform.transform_point2(self.mouse_pos);
+        let (corner, size) = shape_rect(shape, &self.build_sense_points);
+        // a flat shape has nothing to normalize against
+        let mouse_normalized_pos = Vec2::select(
+            size.cmpne(Vec2::ZERO),
+            (mouse_local_pos - corner) / size,
+            Vec2::ZERO,
+        );
 
-            right_holding: self.interactions.right_holding.is_some_and(|v| v == id),
-            right_click_started: self
+        // clicks on children that pass them up count for this one too
+        let clicked = |target: Option<u64>| self.click_chain(target).any(|v| v == id);
+        let pass_through_hovering = self.pass_through_hovering.contains(&id);
+        let old_pass_through_hovering = self.old_pass_through_hovering.contains(&id);
+
+        let right = MouseButton::Right;
+        #[allow(deprecated)]
+        let interactions = Interactions {
+            hovering: self.interactions.hovering.is_some_and(|v| v == id) || pass_through_hovering,
+            hover_started: self.interactions.hover_started.is_some_and(|v| v == id)
+                || (pass_through_hovering && !old_pass_through_hovering),
+            hover_ended: self.interactions.hover_ended.is_some_and(|v| v == id)
+                || (!pass_through_hovering && old_pass_through_hovering),
+            hover_delay_elapsed: self
                 .interactions
-                .right_click_started
+                .hover_delay_elapsed
                 .is_some_and(|v| v == id),
-            right_click_ended: self.interactions.right_click_ended.is_some_and(|v| v == id),
+            hovering_bypass: in_shape,
+
+            holding: clicked(self.interactions.holding),
+            click_started: clicked(self.interactions.click_started),
+            click_ended: clicked(self.interactions.click_ended),
+
+            long_pressed: clicked(self.interactions.long_pressed),
+
+            right_holding: clicked(self.interactions.buttons_holding.get(right)),
+            right_click_started: clicked(self.interactions.buttons_click_started.get(right)),
+            right_click_ended: clicked(self.interactions.buttons_click_ended.get(right)),
+
+            focused: self.interactions.focused.is_some_and(|v| v == id),
+            focus_gained: self.interactions.focus_gained.is_some_and(|v| v == id),
+            focus_lost: self.interactions.focus_lost.is_some_and(|v| v == id),
+
+            dragging,
+            drag_started: self.interactions.drag_started.is_some_and(|v| v == id),
+            drag_ended: self.interactions.drag_ended.is_some_and(|v| v == id),
+
+            drop_hovered: self.interactions.drop_hovered.is_some_and(|v| v == id),
+            dropped: self.interactions.dropped.is_some_and(|v| v == id),
+
+            hover_duration: if self.interactions.hovering.is_some_and(|v| v == id) {
+                self.interactions.hover_duration
+            } else {
+                Duration::ZERO
+            },
+            scroll: if self.interactions.hovering.is_some_and(|v| v == id) {
+                self.interactions.scroll
+            } else {
+                Scroll::default()
+            },
+            gestures: if self.interactions.hovering.is_some_and(|v| v == id) {
+                self.interactions.gestures
+            } else {
+                Gestures::default()
+            },
+
+            click_count: if [
+                self.interactions.holding,
+                self.interactions.click_started,
+                self.interactions.click_ended,
+            ]
+            .into_iter()
+            .any(clicked)
+            {
+                self.interactions.click_count
+            } else {
+                0
+            },
+
+            touches: self
+                .touches
+                .frame
+                .iter()
+                .filter(|v| {
+                    v.sense == Some(id)
+                        && matches!(v.phase, TouchPhase::Started | TouchPhase::Moved)
+                })
+                .count() as u32,
+
+            drag_delta: if dragging {
+                drag_delta.to_array()
+            } else {
+                [0.0; 2]
+            },
+            drag_local_delta: if dragging {
+                local(drag_delta)
+            } else {
+                [0.0; 2]
+            },
+            drag_offset: if dragging {
+                drag_offset.to_array()
+            } else {
+                [0.0; 2]
+            },
+            drag_local_offset: if dragging {
+                local(drag_offset)
+            } else {
+                [0.0; 2]
+            },
+
+            mouse_local_pos: mouse_local_pos.to_array(),
+            mouse_normalized_pos: mouse_normalized_pos.to_array(),
+
+            buttons_holding: self.interactions.buttons_holding.map(clicked),
+            buttons_click_started: self.interactions.buttons_click_started.map(clicked),
+            buttons_click_ended: self.interactions.buttons_click_ended.map(clicked),
+        };
+        if self.same_frame_interactions {
+            self.last_interactions.insert(id, interactions.lasting());
         }
+        interactions
     }
 
     #[builder(finish_fn = test)]
@@ -733,8 +1836,9 @@ impl Stage {
         #[builder(default = 0.0)] w: f32,
         #[builder(default = 0.0)] h: f32,
         #[builder(default = false)] centered: bool,
+        #[builder(default)] options: SenseOptions,
     ) -> Interactions<bool> {
-        let id = self.new_sense_id();
+        let id = self.sense_id(options.key);
 
         let shape = SenseShape {
             typ: SenseShapeType::Rect,
@@ -746,7 +1850,7 @@ impl Stage {
             inv_transform: self.transform.inverse(),
         };
 
-        self.add_sense(shape, id)
+        self.add_sense(shape, id, options)
     }
 
     #[builder(finish_fn = test)]
@@ -757,8 +1861,9 @@ impl Stage {
         #[builder(default = 0.0)] w: f32,
         #[builder(default = 0.0)] h: f32,
         #[builder(default = false)] centered: bool,
+        #[builder(default)] options: SenseOptions,
     ) -> Interactions<bool> {
-        let id = self.new_sense_id();
+        let id = self.sense_id(options.key);
 
         let shape = SenseShape {
             typ: SenseShapeType::Ellipse,
@@ -770,6 +1875,77 @@ impl Stage {
             inv_transform: self.transform.inverse(),
         };
 
-        self.add_sense(shape, id)
+        self.add_sense(shape, id, options)
+    }
+
+    /// senses what is drawn from here until `end_pick_sense` by the pixels that end up on screen,
+    /// so transparent parts of textures, clips and whatever is drawn over it all count.
+    /// it's tested on the gpu, so it lags a frame or two behind the other senses.
+    /// only the mouse (or the touch acting as it) picks it, other touches go through it
+    #[builder(finish_fn = test)]
+    pub fn pick_sense(
+        &mut self,
+        /// how visible a pixel has to be to count
+        #[builder(default = 0.5)]
+        alpha_threshold: f32,
+        #[builder(default)] mut options: SenseOptions,
+    ) -> Interactions<bool> {
+        let id = self.sense_id(options.key);
+
+        // the gpu only has 24 bits for them, the ones past that are added but never picked
+        if self.pick_senses.len() < MAX_PICK_SENSES {
+            self.pick_senses.push(id);
+            let threshold = (alpha_threshold.clamp(0.0, 1.0) * 255.0).round() as u32;
+            self.current_pick = (threshold << 24) | self.pick_senses.len() as u32;
+        } else {
+            self.current_pick = NO_PICK;
+        }
+
+        let shape = SenseShape {
+            typ: SenseShapeType::Pick,
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
+            centered: false,
+            inv_transform: self.transform.inverse(),
+        };
+
+        // the clips are applied on the gpu
+        options.ignore_clip = true;
+        options.pass_through = false;
+        self.add_sense(shape, id, options)
+    }
+
+    #[builder(finish_fn = test)]
+    pub fn path_sense(
+        &mut self,
+        path: &Path,
+        #[builder(default)] fill_rule: FillRule,
+        #[builder(default)] options: SenseOptions,
+    ) -> Interactions<bool> {
+        let id = self.sense_id(options.key);
+
+        let start_point = self.build_sense_points.len();
+        if let Some(tolerance) = self.local_tolerance() {
+            path.flatten_into(tolerance, &mut self.build_sense_points);
+        }
+        let end_point = self.build_sense_points.len();
+
+        let shape = SenseShape {
+            typ: SenseShapeType::Path {
+                start_point: start_point as u32,
+                end_point: end_point as u32,
+                fill_rule,
+            },
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
+            centered: false,
+            inv_transform: self.transform.inverse(),
+        };
+
+        self.add_sense(shape, id, options)
     }
 }
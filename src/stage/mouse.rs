@@ -0,0 +1,76 @@
+use std::mem::take;
+
+use winit::event::MouseButton;
+
+/// a value for each mouse button, only the ones that aren't the default are kept.
+/// winit numbers the extra buttons differently on each platform, so they can't be indexed
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ButtonMap<T>(Vec<(MouseButton, T)>);
+
+impl<T> Default for ButtonMap<T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<T: Copy + Default + PartialEq> ButtonMap<T> {
+    pub(crate) fn get(&self, button: MouseButton) -> T {
+        self.0
+            .iter()
+            .find(|(b, _)| *b == button)
+            .map_or_else(T::default, |(_, v)| *v)
+    }
+    pub(crate) fn set(&mut self, button: MouseButton, value: T) {
+        let idx = self.0.iter().position(|(b, _)| *b == button);
+        match idx {
+            Some(idx) if value == T::default() => {
+                self.0.swap_remove(idx);
+            }
+            Some(idx) => self.0[idx].1 = value,
+            None if value != T::default() => self.0.push((button, value)),
+            None => {}
+        }
+    }
+    /// the buttons that aren't the default
+    pub(crate) fn buttons(&self) -> impl Iterator<Item = MouseButton> {
+        self.0.iter().map(|(b, _)| *b)
+    }
+    pub(crate) fn map<U: Copy + Default + PartialEq>(&self, f: impl Fn(T) -> U) -> ButtonMap<U> {
+        let mut out = ButtonMap::default();
+        for (button, v) in &self.0 {
+            out.set(*button, f(*v));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct MouseButtons {
+    /// the sense each button was pressed on, if any
+    pub(crate) held_senses: ButtonMap<Option<u64>>,
+    pub(crate) down: ButtonMap<bool>,
+    pub(crate) pressed: ButtonMap<bool>,
+    pub(crate) released: ButtonMap<bool>,
+
+    // collected from the events until the next frame starts
+    next_pressed: ButtonMap<bool>,
+    next_released: ButtonMap<bool>,
+}
+
+impl MouseButtons {
+    pub(crate) fn start(&mut self) {
+        self.pressed = take(&mut self.next_pressed);
+        self.released = take(&mut self.next_released);
+    }
+
+    pub(crate) fn press(&mut self, button: MouseButton, sense: Option<u64>) {
+        self.held_senses.set(button, sense);
+        self.down.set(button, true);
+        self.next_pressed.set(button, true);
+    }
+    pub(crate) fn release(&mut self, button: MouseButton) {
+        self.held_senses.set(button, None);
+        self.down.set(button, false);
+        self.next_released.set(button, true);
+    }
+}
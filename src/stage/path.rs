@@ -1,16 +1,54 @@
 use lyon::{
     math::{Angle, Box2D},
     path::{
-        BuilderImpl, Winding,
+        BuilderImpl, PathEvent, Winding,
         builder::{BorderRadii, NoAttributes},
+        iterator::PathIterator,
     },
 };
 
+/// how overlapping parts of a path decide what is inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
 #[derive(Clone)]
 pub struct Path {
     pub(crate) inner: lyon::path::Path,
 }
 
+impl Path {
+    /// pushes the path as one closed polygon.
+    /// all subpaths are joined into one by going back to the very first point after each one,
+    /// the edges going there and back cancel each other out
+    pub(crate) fn flatten_into(&self, tolerance: f32, points: &mut Vec<[f32; 2]>) {
+        let mut first = None;
+        for event in self.inner.iter().flattened(tolerance) {
+            let point = match event {
+                PathEvent::Begin { at } => {
+                    if let Some(first) = first {
+                        points.push(first);
+                    }
+                    at
+                }
+                PathEvent::Line { to, .. } => to,
+                PathEvent::End { first: start, .. } => start,
+                PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => unreachable!(),
+            };
+            let pos = [point.x, point.y];
+
+            first.get_or_insert(pos);
+            points.push(pos);
+        }
+        if let Some(first) = first {
+            points.push(first);
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PathBuilder {
     pub(crate) inner: NoAttributes<BuilderImpl>,
@@ -1,25 +1,164 @@
+use std::{hash::Hash, time::Duration};
+
 use glam::{Affine2, Vec2, vec2};
 
-#[derive(Debug, Clone, Copy)]
+use winit::{event::MouseButton, window::CursorIcon};
+
+use crate::TextureInfo;
+
+use super::{hash_key, mouse::ButtonMap, path::FillRule};
+
+#[derive(Debug, Clone, Default)]
 pub struct Interactions<T> {
+    /// also set for pass through senses above the hovered one
     pub hovering: T,
     pub hovering_bypass: T,
     pub hover_started: T,
     pub hover_ended: T,
+    /// hovered for at least `Stage::hover_delay`, stays set until the hover ends. not set for pass through senses
+    pub hover_delay_elapsed: T,
 
+    // the left button, see `button_holding` and so on for the others
     pub holding: T,
     pub click_started: T,
     pub click_ended: T,
+    /// held for `Stage::long_press_duration` without dragging, only for the frame it happens
+    pub long_pressed: T,
 
+    #[deprecated = "use `button_holding(MouseButton::Right)`"]
     pub right_holding: T,
+    #[deprecated = "use `button_click_started(MouseButton::Right)`"]
     pub right_click_started: T,
+    #[deprecated = "use `button_click_ended(MouseButton::Right)`"]
     pub right_click_ended: T,
+
+    /// see `focus_order` in `SenseOptions`
+    pub focused: T,
+    pub focus_gained: T,
+    pub focus_lost: T,
+
+    /// holding and moved past `Stage::drag_threshold`.
+    /// the sense keeps being dragged and hovered when the mouse leaves it, until released
+    pub dragging: T,
+    pub drag_started: T,
+    pub drag_ended: T,
+
+    /// something with a payload is dragged over this sense, see `Stage::set_drag_payload`
+    pub drop_hovered: T,
+    /// something with a payload was released over this sense, take it with `Stage::take_drag_payload`
+    pub dropped: T,
+
+    /// how long the sense has been hovered, only set for the hovered sense
+    pub hover_duration: Duration,
+    /// only set for the hovered sense
+    pub scroll: Scroll,
+    /// only set for the hovered sense
+    pub gestures: Gestures,
+
+    /// 1 for a single click, 2 for a double click and so on.
+    /// only set for the sense that is held or was just released
+    pub click_count: u32,
+    /// how many touches that started on this sense are down, not set on the stage
+    pub touches: u32,
+
+    // only set for the dragged sense, the local ones are in the space the sense was added in
+    /// how far the mouse moved since the last frame
+    pub drag_delta: [f32; 2],
+    pub drag_local_delta: [f32; 2],
+    /// how far the mouse moved since it was pressed
+    pub drag_offset: [f32; 2],
+    pub drag_local_offset: [f32; 2],
+
+    // always set, also outside of the sense, not set on the stage
+    /// the mouse in the space the sense was added in
+    pub mouse_local_pos: [f32; 2],
+    /// the mouse relative to the sense, 0 to 1 from the (x, y) corner to the opposite one.
+    /// paths use their bounds
+    pub mouse_normalized_pos: [f32; 2],
+
+    // every button, see `button_holding` and so on
+    pub(crate) buttons_holding: ButtonMap<T>,
+    pub(crate) buttons_click_started: ButtonMap<T>,
+    pub(crate) buttons_click_ended: ButtonMap<T>,
+}
+
+impl Interactions<bool> {
+    /// only what lasts from frame to frame, without the things that happen once like clicks
+    pub(crate) fn lasting(&self) -> Self {
+        #[allow(deprecated)]
+        Self {
+            hovering: self.hovering,
+            hovering_bypass: self.hovering_bypass,
+            hover_delay_elapsed: self.hover_delay_elapsed,
+            holding: self.holding,
+            right_holding: self.right_holding,
+            focused: self.focused,
+            dragging: self.dragging,
+            drop_hovered: self.drop_hovered,
+            hover_duration: self.hover_duration,
+            click_count: self.click_count,
+            touches: self.touches,
+            drag_offset: self.drag_offset,
+            drag_local_offset: self.drag_local_offset,
+            mouse_local_pos: self.mouse_local_pos,
+            mouse_normalized_pos: self.mouse_normalized_pos,
+            buttons_holding: self.buttons_holding.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn button_holding(&self, button: MouseButton) -> bool {
+        self.buttons_holding.get(button)
+    }
+    pub fn button_click_started(&self, button: MouseButton) -> bool {
+        self.buttons_click_started.get(button)
+    }
+    pub fn button_click_ended(&self, button: MouseButton) -> bool {
+        self.buttons_click_ended.get(button)
+    }
+}
+
+/// how far the content under the mouse should move, in world directions (so positive y is up)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Scroll {
+    /// from mouse wheels, in lines
+    pub lines: [f32; 2],
+    /// from trackpads and other precise devices, in pixels
+    pub pixels: [f32; 2],
+}
+
+/// trackpad gestures, in world directions like `Scroll`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gestures {
+    /// multiply the scale by this, above 1 means zooming in
+    pub zoom: f32,
+    /// in radians, counterclockwise
+    pub rotation: f32,
+    /// in pixels
+    pub pan: [f32; 2],
+}
+impl Default for Gestures {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            rotation: 0.0,
+            pan: [0.0; 2],
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum SenseShapeType {
     Rect,
     Ellipse,
+    /// the points are in the sense points of the frame the sense was added in
+    Path {
+        start_point: u32,
+        end_point: u32,
+        fill_rule: FillRule,
+    },
+    /// tested on the gpu by what is drawn while it's the current pick sense, see `Stage::pick_sense`
+    Pick,
 }
 #[derive(Debug, Clone, Copy)]
 pub struct SenseShape {
@@ -35,9 +174,59 @@ pub struct SenseShape {
 pub struct SenseSave {
     pub(crate) shape: SenseShape,
     pub(crate) id: u64,
+    pub(crate) clip: u32,
+    pub(crate) options: SenseOptions,
+}
+/// what all sense builders take besides the shape, like
+/// `s.rect_sense().w(20.0).h(20.0).options(SenseOptions::builder().cursor(CursorIcon::Pointer).build())`
+#[derive(Debug, Clone, Copy, Default, bon::Builder)]
+pub struct SenseOptions {
+    /// keep sensing outside of the current clip, useful for things like drag handles.
+    /// pick senses always ignore it, the gpu applies the clips for them
+    #[builder(default)]
+    pub(crate) ignore_clip: bool,
+    /// makes the sense focusable, lower ones come first when tabbing.
+    /// ones with the same order go in the order they were added
+    pub(crate) focus_order: Option<i32>,
+    /// the cursor while this is the hovered sense
+    #[builder(into)]
+    pub(crate) cursor: Option<Cursor>,
+    /// clicks on this sense also go to the parent, and on from there unless it consumes them
+    pub(crate) parent: Option<SenseID>,
+    /// keeps the clicks on this sense and its children from going up to its parent
+    #[builder(default)]
+    pub(crate) consume_clicks: bool,
+    /// gets hovered along with what's below it without blocking it, so it never gets clicks.
+    /// pick senses can't pass through
+    #[builder(default)]
+    pub(crate) pass_through: bool,
+    /// keeps the interactions on this sense when senses before it come and go.
+    /// hashed like the keys of `temp`, only has to be unique in the current `push_id` scope
+    #[builder(with = |key: impl Hash + 'static| hash_key(&key))]
+    pub(crate) key: Option<u64>,
 }
 
-pub fn test_in_shape(shape: SenseShape, pos: Vec2) -> bool {
+/// a sense, the same from frame to frame as long as it's added in the same place or with the same key.
+/// get the one that was just added with `Stage::last_sense_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SenseID(pub(crate) u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    Icon(CursorIcon),
+    /// the hotspot is the pixel of the texture that does the pointing
+    Texture {
+        texture: TextureInfo,
+        hotspot: [u16; 2],
+    },
+}
+impl From<CursorIcon> for Cursor {
+    fn from(icon: CursorIcon) -> Self {
+        Self::Icon(icon)
+    }
+}
+
+pub fn test_in_shape(shape: SenseShape, points: &[[f32; 2]], pos: Vec2) -> bool {
     let pos = shape.inv_transform.transform_point2(pos);
     let [x, y] = [(shape.x, shape.w), (shape.y, shape.h)]
         .map(|(p, d)| if shape.centered { p - d / 2.0 } else { p });
@@ -54,5 +243,66 @@ pub fn test_in_shape(shape: SenseShape, pos: Vec2) -> bool {
             let center = vec2(x, y) + radius;
             (pos - center).length() <= radius
         }
+        SenseShapeType::Path {
+            start_point,
+            end_point,
+            fill_rule,
+        } => {
+            let winding = winding_number(&points[start_point as usize..end_point as usize], pos);
+            match fill_rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            }
+        }
+        // the stage checks these against what the gpu picked
+        SenseShapeType::Pick => false,
+    }
+}
+
+/// the (x, y) corner and the size of the shape in its local space, the bounds for paths
+pub fn shape_rect(shape: SenseShape, points: &[[f32; 2]]) -> (Vec2, Vec2) {
+    match shape.typ {
+        SenseShapeType::Path {
+            start_point,
+            end_point,
+            ..
+        } => {
+            let points = points[start_point as usize..end_point as usize]
+                .iter()
+                .map(|v| Vec2::from(*v));
+            let min = points.clone().reduce(Vec2::min).unwrap_or_default();
+            let max = points.reduce(Vec2::max).unwrap_or_default();
+            (min, max - min)
+        }
+        SenseShapeType::Rect | SenseShapeType::Ellipse => {
+            let size = vec2(shape.w, shape.h);
+            let corner = vec2(shape.x, shape.y);
+            if shape.centered {
+                (corner - size / 2.0, size)
+            } else {
+                (corner, size)
+            }
+        }
+        // the bounds of what was drawn aren't known here
+        SenseShapeType::Pick => (Vec2::ZERO, Vec2::ZERO),
+    }
+}
+
+/// how many times the polygon goes around `pos`, counterclockwise is positive
+fn winding_number(points: &[[f32; 2]], pos: Vec2) -> i32 {
+    let mut winding = 0;
+    for (i, a) in points.iter().enumerate() {
+        let [a, b] = [*a, points[(i + 1) % points.len()]].map(Vec2::from);
+
+        // which side of the edge `pos` is on
+        let side = (b - a).perp_dot(pos - a);
+        if a.y <= pos.y {
+            if b.y > pos.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= pos.y && side < 0.0 {
+            winding -= 1;
+        }
     }
+    winding
 }
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use glam::{Vec2, vec2};
+use itertools::Itertools;
+
+use super::sense::{SenseSave, SenseShapeType, shape_rect};
+
+/// in pixels
+const CELL_SIZE: f32 = 64.0;
+/// senses covering more cells than this are tested everywhere instead
+const MAX_CELLS: i64 = 64;
+
+/// finds the senses that might be at a position, so not all of them have to be tested
+#[derive(Debug, Default)]
+pub(crate) struct SenseGrid {
+    // indices into the senses, in the order they were added
+    cells: HashMap<(i32, i32), Vec<u32>>,
+    // too big for the cells, or transformed so they don't have proper bounds
+    everywhere: Vec<u32>,
+}
+
+impl SenseGrid {
+    pub(crate) fn rebuild(&mut self, senses: &[SenseSave], points: &[[f32; 2]]) {
+        // keep the cells that were used last time, they probably will be again
+        self.cells.retain(|_, v| {
+            let used = !v.is_empty();
+            v.clear();
+            used
+        });
+        self.everywhere.clear();
+
+        for (i, sense) in senses.iter().enumerate() {
+            if let SenseShapeType::Pick = sense.shape.typ {
+                self.everywhere.push(i as u32);
+                continue;
+            }
+
+            let (corner, size) = shape_rect(sense.shape, points);
+            let transform = sense.shape.inv_transform.inverse();
+            let corners = [
+                corner,
+                corner + vec2(size.x, 0.0),
+                corner + vec2(0.0, size.y),
+                corner + size,
+            ]
+            .map(|p| transform.transform_point2(p));
+            let min = corners.into_iter().reduce(Vec2::min).unwrap();
+            let max = corners.into_iter().reduce(Vec2::max).unwrap();
+
+            let [min, max] = [min, max].map(cell_of);
+            let n_cells = (max.0 as i64 - min.0 as i64 + 1) * (max.1 as i64 - min.1 as i64 + 1);
+            if !(corners.iter().all(|v| v.is_finite()) && n_cells <= MAX_CELLS) {
+                self.everywhere.push(i as u32);
+                continue;
+            }
+
+            for cell in (min.0..=max.0).cartesian_product(min.1..=max.1) {
+                self.cells.entry(cell).or_default().push(i as u32);
+            }
+        }
+    }
+
+    /// the senses that might be at `pos`, the last added first
+    pub(crate) fn query(&self, pos: Vec2) -> impl Iterator<Item = usize> {
+        let cell = self.cells.get(&cell_of(pos)).map_or(&[][..], Vec::as_slice);
+        cell.iter()
+            .rev()
+            .merge_by(self.everywhere.iter().rev(), |a, b| a > b)
+            .map(|i| *i as usize)
+    }
+}
+
+fn cell_of(pos: Vec2) -> (i32, i32) {
+    let cell = (pos / CELL_SIZE).floor();
+    (cell.x as i32, cell.y as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Affine2;
+
+    use super::*;
+    use crate::stage::sense::{SenseOptions, SenseShape};
+
+    fn sense(typ: SenseShapeType, x: f32, y: f32, w: f32, h: f32) -> SenseSave {
+        SenseSave {
+            shape: SenseShape {
+                typ,
+                x,
+                y,
+                w,
+                h,
+                centered: false,
+                inv_transform: Affine2::IDENTITY,
+            },
+            id: 0,
+            clip: 0,
+            options: SenseOptions::default(),
+        }
+    }
+
+    fn grid(senses: &[SenseSave]) -> SenseGrid {
+        let mut grid = SenseGrid::default();
+        grid.rebuild(senses, &[]);
+        grid
+    }
+
+    #[test]
+    fn spans_cells() {
+        let grid = grid(&[sense(SenseShapeType::Rect, 10.0, 10.0, 200.0, 100.0)]);
+        for pos in [vec2(11.0, 11.0), vec2(150.0, 50.0), vec2(205.0, 105.0)] {
+            assert_eq!(grid.query(pos).collect_vec(), [0]);
+        }
+        assert!(grid.query(vec2(-100.0, 11.0)).next().is_none());
+        assert!(grid.everywhere.is_empty());
+    }
+
+    #[test]
+    fn outside_window() {
+        let grid = grid(&[sense(SenseShapeType::Ellipse, -5000.0, 3000.0, 20.0, 20.0)]);
+        assert_eq!(grid.query(vec2(-4990.0, 3010.0)).collect_vec(), [0]);
+        assert!(grid.query(Vec2::ZERO).next().is_none());
+    }
+
+    #[test]
+    fn too_big_goes_everywhere() {
+        let grid = grid(&[sense(SenseShapeType::Rect, 0.0, 0.0, f32::INFINITY, 10.0)]);
+        assert_eq!(grid.everywhere, [0]);
+        assert_eq!(grid.query(vec2(-1e6, 1e6)).collect_vec(), [0]);
+    }
+
+    #[test]
+    fn keeps_order_with_everywhere() {
+        let grid = grid(&[
+            sense(SenseShapeType::Rect, 0.0, 0.0, 10.0, 10.0),
+            sense(SenseShapeType::Pick, 0.0, 0.0, 0.0, 0.0),
+            sense(SenseShapeType::Rect, 0.0, 0.0, 10.0, 10.0),
+            sense(SenseShapeType::Rect, -1e5, -1e5, 2e5, 2e5),
+            sense(SenseShapeType::Rect, 0.0, 0.0, 10.0, 10.0),
+            sense(SenseShapeType::Rect, 500.0, 500.0, 10.0, 10.0),
+        ]);
+        assert_eq!(grid.query(vec2(5.0, 5.0)).collect_vec(), [4, 3, 2, 1, 0]);
+        assert_eq!(grid.query(vec2(505.0, 505.0)).collect_vec(), [5, 3, 1]);
+    }
+}
@@ -0,0 +1,55 @@
+use glam::Vec2;
+use winit::event::TouchPhase;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Touch {
+    pub id: u64,
+    pub pos: [f32; 2],
+    /// `Started` and `Ended`/`Cancelled` only for the frame it happened, `Moved` in between
+    pub phase: TouchPhase,
+    // the sense it started on
+    pub(crate) sense: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Touches {
+    pub(crate) frame: Vec<Touch>,
+    // collected from the events until the next frame starts
+    pub(crate) next_frame: Vec<Touch>,
+    /// the first touch is also the mouse
+    pub(crate) primary: Option<u64>,
+    /// where it was lifted since the last frame, the mouse leaves once that frame saw it
+    pub(crate) primary_lifted: Option<Vec2>,
+}
+
+impl Touches {
+    pub(crate) fn start(&mut self) {
+        self.frame.clone_from(&self.next_frame);
+
+        self.next_frame
+            .retain(|v| matches!(v.phase, TouchPhase::Started | TouchPhase::Moved));
+        for touch in &mut self.next_frame {
+            touch.phase = TouchPhase::Moved;
+        }
+    }
+
+    pub(crate) fn event(&mut self, id: u64, pos: Vec2, phase: TouchPhase, sense: Option<u64>) {
+        let pos = pos.to_array();
+
+        match self.next_frame.iter_mut().find(|v| v.id == id) {
+            // it only counts as moving if it didn't start this frame
+            Some(touch) if phase == TouchPhase::Moved => touch.pos = pos,
+            Some(touch) => {
+                touch.pos = pos;
+                touch.phase = phase;
+            }
+            None if phase == TouchPhase::Started => self.next_frame.push(Touch {
+                id,
+                pos,
+                phase,
+                sense,
+            }),
+            None => {}
+        }
+    }
+}
@@ -5,8 +5,12 @@ use std::time::Instant;
 
 use crate::{
     Stage, TextureInfo,
-    render::{gpu::GPUData, shaders::wgsl_main, texture::Texture},
-    state::texture::{LoadedTexture, TextureMap},
+    render::{
+        gpu::GPUData,
+        shaders::wgsl_main,
+        texture::{Texture, TextureReadback},
+    },
+    state::texture::{LoadedTexture, TextureKey, TextureMap},
 };
 
 pub trait AppState {
@@ -18,6 +22,8 @@ pub trait AppState {
 pub struct AppData {
     // pub(crate) window: Arc<winit::window::Window>,
     pub(crate) loaded_textures: TextureMap,
+    // removed since the window last looked, so it can drop what it made from them
+    pub(crate) removed_textures: Vec<TextureKey>,
 
     pub(crate) gpu_data: GPUData,
 }
@@ -43,7 +49,10 @@ impl AppData {
             } else {
                 wgpu::FilterMode::Linear
             },
-            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            // copy src so it can be read back for cursors
+            wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
         );
         let bind_group = wgsl_main::globals::BindGroup1::from_bindings(
             &self.gpu_data.device,
@@ -61,7 +70,19 @@ impl AppData {
         TextureInfo { key, width, height }
     }
     pub fn remove_texture(&mut self, texture: TextureInfo) {
-        self.loaded_textures.remove(texture.key);
+        if self.loaded_textures.remove(texture.key).is_some() {
+            self.removed_textures.push(texture.key);
+        }
+    }
+    /// `None` if the texture was removed
+    pub(crate) fn start_read_texture_rgba(&self, texture: TextureInfo) -> Option<TextureReadback> {
+        let loaded = self.loaded_textures.get(texture.key)?;
+        Some(loaded.texture.start_read_rgba(
+            &self.gpu_data.device,
+            &self.gpu_data.queue,
+            texture.width,
+            texture.height,
+        ))
     }
 }
 
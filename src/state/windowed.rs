@@ -1,18 +1,124 @@
 use std::{
+    collections::HashMap,
     sync::Arc,
     thread,
     time::{Duration, Instant},
 };
 
-use glam::vec2;
+use glam::{Vec2, vec2};
 use slotmap::SlotMap;
-use winit::window::Window;
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event_loop::ActiveEventLoop,
+    window::{CursorIcon, CustomCursor, Window},
+};
 
-use crate::{AppData, AppState, Stage, render::gpu::GPUData, state::AppBundle};
+use crate::{
+    AppData, AppState, InputEvent, Stage,
+    render::{gpu::GPUData, texture::TextureReadback},
+    stage::sense::Cursor,
+    state::AppBundle,
+};
 
 struct App<S> {
     window: Arc<Window>,
     bundle: AppBundle<S>,
+    // what was last sent to the window, so it's only updated on changes
+    ime_caret: Option<[Vec2; 2]>,
+    cursor: Cursor,
+    // `None` for the ones that can't be cursors, those show the default one
+    custom_cursors: HashMap<Cursor, Option<CustomCursor>>,
+    // the texture of a custom cursor coming back from the gpu, the last cursor stays until it's here
+    pending_cursor: Option<(Cursor, TextureReadback)>,
+}
+
+impl<S> App<S> {
+    fn update_cursor(&mut self, cursor: Cursor, event_loop: &ActiveEventLoop) {
+        for key in self.bundle.data.removed_textures.drain(..) {
+            let uses_key = |cursor: &Cursor| matches!(cursor, Cursor::Texture { texture, .. } if texture.key == key);
+            self.custom_cursors.retain(|cursor, _| !uses_key(cursor));
+            if self
+                .pending_cursor
+                .as_ref()
+                .is_some_and(|(cursor, _)| uses_key(cursor))
+            {
+                self.pending_cursor = None;
+            }
+        }
+
+        if let Some((pending, readback)) = &self.pending_cursor
+            && let Some(rgba) = readback.try_take(&self.bundle.data.gpu_data.device)
+        {
+            let pending = *pending;
+            self.pending_cursor = None;
+            let custom = custom_cursor(pending, rgba, event_loop);
+            self.custom_cursors.insert(pending, custom);
+        }
+
+        if cursor == self.cursor {
+            return;
+        }
+        match cursor {
+            Cursor::Icon(icon) => self.window.set_cursor(icon),
+            Cursor::Texture { texture, hotspot } => {
+                if !self.custom_cursors.contains_key(&cursor) {
+                    if self.pending_cursor.is_some() {
+                        // tried again once the other one is done
+                        return;
+                    }
+                    if texture.width > u16::MAX as u32
+                        || texture.height > u16::MAX as u32
+                        || hotspot[0] as u32 >= texture.width
+                        || hotspot[1] as u32 >= texture.height
+                    {
+                        log::warn!(
+                            "a {}x{} texture with the hotspot at {hotspot:?} can't be a cursor",
+                            texture.width,
+                            texture.height,
+                        );
+                        self.custom_cursors.insert(cursor, None);
+                    } else if let Some(readback) = self.bundle.data.start_read_texture_rgba(texture)
+                    {
+                        self.pending_cursor = Some((cursor, readback));
+                        return;
+                    } else {
+                        log::warn!("the texture of the cursor was removed");
+                        self.custom_cursors.insert(cursor, None);
+                    }
+                }
+                match &self.custom_cursors[&cursor] {
+                    Some(custom) => self.window.set_cursor(custom.clone()),
+                    None => self.window.set_cursor(CursorIcon::Default),
+                }
+            }
+        }
+        self.cursor = cursor;
+    }
+}
+
+/// `None` and a warning if winit doesn't take it
+fn custom_cursor(
+    cursor: Cursor,
+    rgba: Vec<u8>,
+    event_loop: &ActiveEventLoop,
+) -> Option<CustomCursor> {
+    let Cursor::Texture { texture, hotspot } = cursor else {
+        return None;
+    };
+    let source = CustomCursor::from_rgba(
+        rgba,
+        texture.width as u16,
+        texture.height as u16,
+        hotspot[0],
+        hotspot[1],
+    );
+    match source {
+        Ok(source) => Some(event_loop.create_custom_cursor(source)),
+        Err(err) => {
+            log::warn!("texture can't be used as a cursor: {err}");
+            None
+        }
+    }
 }
 
 struct AppHandler<S> {
@@ -42,6 +148,7 @@ where
                 // window,
                 gpu_data,
                 loaded_textures: SlotMap::default(),
+                removed_textures: vec![],
             };
             let state = S::setup(&mut data);
 
@@ -49,6 +156,10 @@ where
 
             self.app = Some(App {
                 window,
+                ime_caret: None,
+                cursor: Cursor::Icon(Default::default()),
+                custom_cursors: HashMap::new(),
+                pending_cursor: None,
                 bundle: AppBundle {
                     data,
                     stage,
@@ -75,54 +186,124 @@ where
                     app.bundle.data.gpu_data.resize(to.width, to.height);
                 }
                 winit::event::WindowEvent::RedrawRequested => {
-                    app.bundle.stage.start();
-
                     let now = Instant::now();
                     let delta = now - app.bundle.last_render;
                     app.bundle.last_render = now;
 
                     app.bundle.stage.delta = delta.as_secs_f64();
+                    let w_size = app.window.inner_size();
+                    app.bundle.stage.window_size = vec2(w_size.width as f32, w_size.height as f32);
+                    app.bundle.stage.picked_sense = app.bundle.data.gpu_data.picked_sense;
+                    // a replayed frame brings its own delta and window size
+                    app.bundle.stage.apply_replay();
+                    if app.bundle.stage.same_frame_interactions {
+                        app.bundle.stage.start_layout_pass();
+                        app.bundle
+                            .state
+                            .render(&mut app.bundle.stage, &mut app.bundle.data);
+                        app.bundle.stage.finish(&mut app.bundle.data);
+                    }
+                    app.bundle.stage.start();
 
                     app.bundle
                         .state
                         .render(&mut app.bundle.stage, &mut app.bundle.data);
+                    app.bundle.stage.finish(&mut app.bundle.data);
 
                     app.bundle
                         .data
                         .gpu_data
                         .render(&app.bundle.stage, &app.bundle.data.loaded_textures);
 
+                    let ime_caret = app.bundle.stage.ime_caret;
+                    if ime_caret.is_some() != app.ime_caret.is_some() {
+                        app.window.set_ime_allowed(ime_caret.is_some());
+                    }
+                    if let Some([min, max]) = ime_caret
+                        && app.ime_caret != ime_caret
+                    {
+                        let w_size = app.window.inner_size();
+                        app.window.set_ime_cursor_area(
+                            PhysicalPosition::new(
+                                min.x + w_size.width as f32 / 2.0,
+                                -max.y + w_size.height as f32 / 2.0,
+                            ),
+                            PhysicalSize::new(max.x - min.x, max.y - min.y),
+                        );
+                    }
+                    app.ime_caret = ime_caret;
+
+                    let cursor = app.bundle.stage.cursor();
+                    app.update_cursor(cursor, event_loop);
+
                     app.bundle.data.gpu_data.mask_atlas.clear_in_use();
                     app.bundle.data.gpu_data.color_atlas.clear_in_use();
 
                     app.window.request_redraw();
                 }
                 winit::event::WindowEvent::CursorMoved { position, .. } => {
-                    let w_size = app.window.inner_size();
-
-                    app.bundle.stage.mouse_pos = vec2(
-                        position.x as f32 - w_size.width as f32 / 2.0,
-                        -(position.y as f32 - w_size.height as f32 / 2.0),
-                    );
+                    let pos = to_stage_pos(&app.window, position);
+                    app.bundle
+                        .stage
+                        .input(InputEvent::MouseMoved(pos.to_array()));
+                }
+                winit::event::WindowEvent::Touch(touch) => {
+                    let pos = to_stage_pos(&app.window, touch.location);
+                    app.bundle.stage.input(InputEvent::Touch {
+                        id: touch.id,
+                        pos: pos.to_array(),
+                        phase: touch.phase,
+                    });
                 }
                 winit::event::WindowEvent::MouseInput { state, button, .. } => {
-                    match (button, state.is_pressed()) {
-                        (winit::event::MouseButton::Left, true) => {
-                            app.bundle.stage.mouse_down =
-                                app.bundle.stage.find_top_old_sense().map(|v| v.id);
-                        }
-                        (winit::event::MouseButton::Left, false) => {
-                            app.bundle.stage.mouse_down = None;
-                        }
-                        (winit::event::MouseButton::Right, true) => {
-                            app.bundle.stage.right_mouse_down =
-                                app.bundle.stage.find_top_old_sense().map(|v| v.id);
+                    if state.is_pressed() {
+                        app.bundle.stage.input(InputEvent::MousePressed(button));
+                    } else {
+                        app.bundle.stage.input(InputEvent::MouseReleased(button));
+                    }
+                }
+                winit::event::WindowEvent::MouseWheel { delta, .. } => {
+                    // winit has y going down
+                    let event = match delta {
+                        winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                            InputEvent::ScrollLines([x, -y])
                         }
-                        (winit::event::MouseButton::Right, false) => {
-                            app.bundle.stage.right_mouse_down = None;
+                        winit::event::MouseScrollDelta::PixelDelta(pos) => {
+                            InputEvent::ScrollPixels([pos.x as f32, -pos.y as f32])
                         }
-                        _ => {}
-                    }
+                    };
+                    app.bundle.stage.input(event);
+                }
+                winit::event::WindowEvent::PinchGesture { delta, .. } => {
+                    app.bundle.stage.input(InputEvent::Zoom(1.0 + delta as f32));
+                }
+                winit::event::WindowEvent::RotationGesture { delta, .. } => {
+                    app.bundle
+                        .stage
+                        .input(InputEvent::Rotate(delta.to_radians()));
+                }
+                winit::event::WindowEvent::PanGesture { delta, .. } => {
+                    app.bundle.stage.input(InputEvent::Pan([delta.x, -delta.y]));
+                }
+                winit::event::WindowEvent::KeyboardInput { event, .. } => {
+                    app.bundle.stage.input(InputEvent::Key {
+                        physical: event.physical_key,
+                        logical: event.logical_key,
+                        text: event.text.map(|t| t.to_string()),
+                        pressed: event.state.is_pressed(),
+                        repeat: event.repeat,
+                    });
+                }
+                winit::event::WindowEvent::Ime(ime) => {
+                    app.bundle.stage.input(InputEvent::Ime(ime));
+                }
+                winit::event::WindowEvent::ModifiersChanged(modifiers) => {
+                    app.bundle
+                        .stage
+                        .input(InputEvent::Modifiers(modifiers.state()));
+                }
+                winit::event::WindowEvent::Focused(false) => {
+                    app.bundle.stage.input(InputEvent::FocusLost);
                 }
                 _ => {}
             }
@@ -148,6 +329,15 @@ where
     }
 }
 
+/// the stage has the origin in the middle and y going up
+fn to_stage_pos(window: &Window, position: PhysicalPosition<f64>) -> Vec2 {
+    let w_size = window.inner_size();
+    vec2(
+        position.x as f32 - w_size.width as f32 / 2.0,
+        -(position.y as f32 - w_size.height as f32 / 2.0),
+    )
+}
+
 pub fn run_app_windowed<S: AppState>() {
     let event_loop = winit::event_loop::EventLoop::builder().build().unwrap();
     event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);